@@ -1,5 +1,12 @@
-use crate::univariate::{UPoly, Root};
+use std::cmp::Ordering;
+use std::ops;
+
+use crate::cad::resultant::{resultant, subresultants};
 use crate::field::Field;
+use crate::interval::Interval;
+use crate::poly::Poly;
+use crate::rational::{Inexact, Rat};
+use crate::univariate::{Root, UPoly};
 
 #[derive(Debug, Clone)]
 pub struct Algebraic<T: Field> {
@@ -14,6 +21,13 @@ pub fn get_roots<T: Field>(p: UPoly<T>, tolerance: T) -> Vec<Algebraic<T>> {
     }).collect()
 }
 
+// exact roots over the squarefree part of p; intervals start coarse and are refined on demand
+pub fn real_roots<T: Field>(p: &UPoly<T>) -> Vec<Algebraic<T>> {
+    let p = p.squarefree_part();
+
+    get_roots(p, T::one() / T::from(16))
+}
+
 impl<T: Field> Algebraic<T> {
     pub fn from_point(point: T) -> Self {
         Algebraic { p: 
@@ -22,4 +36,518 @@ impl<T: Field> Algebraic<T> {
             n: 0
         }
     }   
-}
\ No newline at end of file
+
+    pub fn is_rational(&self) -> bool {
+        matches!(self.val, Root::Point(_))
+    }
+
+    pub fn interval(&self) -> Interval<T> {
+        let (start, end) = self.val.bounds();
+        Interval::new(start, end)
+    }
+
+    // halve the isolating interval; p must be squarefree
+    pub fn refine(&mut self) {
+        if let Root::Interval(start, end) = &self.val {
+            let mid = (start.clone() + end.clone()) / T::from(2);
+            let mid_sign = self.p.eval(&mid).cmp(&T::zero());
+
+            self.val = if mid_sign == Ordering::Equal {
+                Root::Point(mid)
+            } else if self.p.eval(start).cmp(&T::zero()) == mid_sign {
+                Root::Interval(mid, end.clone())
+            } else {
+                Root::Interval(start.clone(), mid)
+            };
+        }
+    }
+
+    // g must divide p; a root of g in the isolating interval can only be this one
+    fn is_root_of_factor(&self, g: &UPoly<T>) -> bool {
+        match &self.val {
+            Root::Point(x) => g.eval(x).is_zero(),
+            Root::Interval(start, end) => {
+                g.eval(start).cmp(&T::zero()) != g.eval(end).cmp(&T::zero())
+            }
+        }
+    }
+
+    // exact sign of q at this number
+    pub fn sign_at(&self, q: &UPoly<T>) -> Ordering {
+        if q.is_zero() || self.is_root_of_factor(&self.p.gcd(q)) {
+            return Ordering::Equal;
+        }
+
+        let mut refined = self.clone();
+
+        loop {
+            if let Root::Point(x) = &refined.val {
+                return q.eval(x).cmp(&T::zero());
+            }
+
+            if let Some(sign) = q.eval_interval(&refined.interval()).sign() {
+                return sign;
+            }
+
+            refined.refine();
+        }
+    }
+
+    pub fn sign(&self) -> Ordering {
+        self.sign_at(&UPoly(vec![T::one(), T::zero()]))
+    }
+
+    // None for zero
+    pub fn inv(&self) -> Option<Self> {
+        if self.sign() == Ordering::Equal {
+            return None;
+        }
+
+        if let Root::Point(x) = &self.val {
+            return Some(Algebraic::from_point(T::one() / x.clone()));
+        }
+
+        // x^d p(1/x) has the reciprocals of p's nonzero roots
+        let reversed = UPoly(self.p.0.iter().rev().cloned().collect()).trim();
+        let mut a = self.clone();
+
+        Some(locate(&reversed, || {
+            while a.interval().sign().is_none() {
+                a.refine();
+            }
+
+            let int = a.interval();
+            a.refine();
+            Interval::new(T::one() / int.hi, T::one() / int.lo)
+        }))
+    }
+
+    // the number q(self)
+    pub fn eval(&self, q: &UPoly<T>) -> Self {
+        if let Root::Point(x) = &self.val {
+            return Algebraic::from_point(q.eval(x));
+        }
+
+        const Y: usize = 0;
+        const Z: usize = 1;
+
+        // q(y) - z and p(y) share a root exactly when z = q(y) for a root y of p
+        let values = resultant(&(q.to_poly(Y) - Poly::var(Z, 1)), &self.p.to_poly(Y), Y);
+        let mut a = self.clone();
+
+        locate(&UPoly::from_poly(&values, Z), || {
+            let int = q.eval_interval(&a.interval());
+            a.refine();
+            int
+        })
+    }
+
+    pub fn compare(&self, other: &Algebraic<T>) -> Ordering {
+        let mut lhs = self.clone();
+        let mut rhs = other.clone();
+
+        loop {
+            match (&lhs.val, &rhs.val) {
+                (Root::Point(x), Root::Point(y)) => return x.cmp(y),
+                (Root::Point(x), _) => {
+                    if !rhs.interval().contains(x) {
+                        return x.cmp(&rhs.interval().lo);
+                    } else if rhs.p.eval(x).is_zero() {
+                        return Ordering::Equal;
+                    }
+
+                    rhs.refine();
+                }
+                (_, Root::Point(_)) => return rhs.compare(&lhs).reverse(),
+                _ => {
+                    let (lhs_int, rhs_int) = (lhs.interval(), rhs.interval());
+
+                    if lhs_int.hi < rhs_int.lo {
+                        return Ordering::Less;
+                    } else if rhs_int.hi < lhs_int.lo {
+                        return Ordering::Greater;
+                    }
+
+                    // equal numbers are a common root in the overlap
+                    let g = lhs.p.gcd(&rhs.p);
+                    let overlap = Root::Interval(
+                        std::cmp::max(lhs_int.lo, rhs_int.lo),
+                        std::cmp::min(lhs_int.hi, rhs_int.hi),
+                    );
+
+                    if g.deg() > 0
+                        && lhs.is_root_of_factor(&g)
+                        && (Algebraic { p: g.clone(), val: overlap, n: 0 }).is_root_of_factor(&g)
+                    {
+                        return Ordering::Equal;
+                    }
+
+                    lhs.refine();
+                    rhs.refine();
+                }
+            }
+        }
+    }
+}
+
+impl Algebraic<Rat> {
+    // the same number, defined by the irreducible factor of p it is a root of; fails when that
+    // factor's coefficients do not fit in a Rat
+    pub fn minimal(&self) -> Result<Self, Inexact> {
+        if let Root::Point(x) = &self.val {
+            return Ok(Algebraic::from_point(*x));
+        }
+
+        let (_, factors) = self.p.factor()?;
+        let (p, _) = factors
+            .into_iter()
+            .find(|(factor, _)| self.is_root_of_factor(factor))
+            .unwrap();
+
+        let mut refined = self.clone();
+
+        Ok(locate(&p, || {
+            refined.refine();
+            refined.interval()
+        }))
+    }
+}
+
+// the root of q whose isolating interval alone meets every enclosure; each call to
+// enclose must return a tighter interval around the number, converging to it
+fn locate<T: Field>(q: &UPoly<T>, mut enclose: impl FnMut() -> Interval<T>) -> Algebraic<T> {
+    let mut candidates = real_roots(q);
+
+    loop {
+        let target = enclose();
+        let overlapping = candidates
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.interval().overlaps(&target))
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+
+        if let [i] = overlapping[..] {
+            return candidates.swap_remove(i);
+        }
+
+        for i in overlapping {
+            candidates[i].refine();
+        }
+    }
+}
+
+// q(x - y) in x and y
+fn shifted<T: Field>(q: &UPoly<T>, x: usize, y: usize) -> Poly<T> {
+    let shift = Poly::var(x, 1) - Poly::var(y, 1);
+
+    q.0.iter().fold(Poly::constant(T::zero()), |acc, coef| {
+        acc * shift.clone() + Poly::constant(coef.clone())
+    })
+}
+
+// y^d q(x / y) in x and y
+fn homogenized<T: Field>(q: &UPoly<T>, x: usize, y: usize) -> Poly<T> {
+    let mut y_pow = Poly::constant(T::one());
+
+    q.0.iter().fold(Poly::constant(T::zero()), |acc, coef| {
+        let next = acc * Poly::var(x, 1) + y_pow.clone() * Poly::constant(coef.clone());
+        y_pow = y_pow.clone() * Poly::var(y, 1);
+        next
+    })
+}
+
+impl<T: Field> PartialEq for Algebraic<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.compare(other) == Ordering::Equal
+    }
+}
+
+impl<T: Field> Eq for Algebraic<T> {}
+
+impl<T: Field> PartialOrd for Algebraic<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Field> Ord for Algebraic<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.compare(other)
+    }
+}
+
+impl<T: Field> ops::Neg for Algebraic<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        let p = UPoly(
+            self.p
+                .0
+                .iter()
+                .rev()
+                .enumerate()
+                .map(|(i, c)| if i % 2 == 0 { c.clone() } else { c.clone() * -1 })
+                .rev()
+                .collect(),
+        );
+
+        let val = match self.val {
+            Root::Point(x) => Root::Point(x * -1),
+            Root::Interval(start, end) => Root::Interval(end * -1, start * -1),
+        };
+
+        // p(-x) has the mirrored roots, so the index counts from the other end
+        let n = get_roots(p.clone(), T::one()).len() - 1 - self.n;
+
+        Algebraic { p, val, n }
+    }
+}
+
+impl<T: Field> ops::Add for Algebraic<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        if let (Root::Point(x), Root::Point(y)) = (&self.val, &rhs.val) {
+            return Algebraic::from_point(x.clone() + y.clone());
+        }
+
+        const Y: usize = 0;
+        const Z: usize = 1;
+
+        // z = a + b is a root of res_y(p_a(z - y), p_b(y))
+        let sum = resultant(&shifted(&self.p, Z, Y), &rhs.p.to_poly(Y), Y);
+        let (mut a, mut b) = (self, rhs);
+
+        locate(&UPoly::from_poly(&sum, Z), || {
+            let int = a.interval() + b.interval();
+            a.refine();
+            b.refine();
+            int
+        })
+    }
+}
+
+impl<T: Field> ops::Sub for Algebraic<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl<T: Field> ops::Mul for Algebraic<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        if self.sign() == Ordering::Equal || rhs.sign() == Ordering::Equal {
+            return Algebraic::from_point(T::zero());
+        }
+
+        if let (Root::Point(x), Root::Point(y)) = (&self.val, &rhs.val) {
+            return Algebraic::from_point(x.clone() * y.clone());
+        }
+
+        const Y: usize = 0;
+        const Z: usize = 1;
+
+        // z = a * b is a root of res_y(y^d p_a(z / y), p_b(y)) when b is nonzero
+        let product = resultant(&homogenized(&self.p, Z, Y), &rhs.p.to_poly(Y), Y);
+        let (mut a, mut b) = (self, rhs);
+
+        locate(&UPoly::from_poly(&product, Z), || {
+            let int = a.interval() * b.interval();
+            a.refine();
+            b.refine();
+            int
+        })
+    }
+}
+
+// an algebraic number z with a and b both polynomials in z, modulo z's defining polynomial
+pub fn primitive_element<T: Field>(
+    a: &Algebraic<T>,
+    b: &Algebraic<T>,
+) -> (Algebraic<T>, UPoly<T>, UPoly<T>) {
+    const Y: usize = 0;
+    const Z: usize = 1;
+
+    let ident = UPoly(vec![T::one(), T::zero()]);
+
+    if let Root::Point(x) = &a.val {
+        return (b.clone(), UPoly(vec![x.clone()]).trim(), ident);
+    }
+
+    if let Root::Point(x) = &b.val {
+        return (a.clone(), ident, UPoly(vec![x.clone()]).trim());
+    }
+
+    let a_poly = a.p.to_poly(Y);
+
+    // z = b + t * a separates every pair of conjugates for all but finitely many t
+    for t in (1..).flat_map(|t| [t, -t]) {
+        let shifted = Poly::var(Z, 1) - Poly::var(Y, 1) * Poly::constant(T::from(t));
+        let b_shifted = b.p.0.iter().fold(Poly::constant(T::zero()), |acc, coef| {
+            acc * shifted.clone() + Poly::constant(coef.clone())
+        });
+
+        let norm = UPoly::from_poly(&resultant(&b_shifted, &a_poly, Y), Z).monic();
+
+        if norm.gcd(&norm.derivative()).deg() > 0 {
+            continue;
+        }
+
+        let mut candidates = get_roots(norm.clone(), T::one() / T::from(16));
+        let (mut a, mut b) = (a.clone(), b.clone());
+
+        let z_index = loop {
+            let target = Interval::point(T::from(t)) * a.interval() + b.interval();
+            let overlapping = candidates
+                .iter()
+                .enumerate()
+                .filter(|(_, c)| c.interval().overlaps(&target))
+                .map(|(i, _)| i)
+                .collect::<Vec<_>>();
+
+            if overlapping.len() == 1 {
+                break overlapping[0];
+            }
+
+            for i in overlapping {
+                candidates[i].refine();
+            }
+
+            a.refine();
+            b.refine();
+        };
+
+        // a = -s0(z) / s1(z) from the first subresultant, the linear gcd in y
+        let (hi, lo) = if b.p.deg() >= a.p.deg() {
+            (&b_shifted, &a_poly)
+        } else {
+            (&a_poly, &b_shifted)
+        };
+
+        let mut srs = subresultants(hi, lo, Y);
+        let s1 = srs.swap_remove(lo.deg(Y));
+        let (s1, s0) = (UPoly::from_poly(&s1[0], Z), UPoly::from_poly(&s1[1], Z));
+
+        let g = s1.gcd(&norm);
+
+        if candidates[z_index].is_root_of_factor(&g) {
+            continue;
+        }
+
+        let z_poly = norm.div_rem(&g).0;
+        let z = Algebraic {
+            p: z_poly.clone(),
+            val: candidates[z_index].val.clone(),
+            n: candidates[..z_index]
+                .iter()
+                .filter(|c| !c.is_root_of_factor(&g))
+                .count(),
+        };
+
+        let inv = s1.inverse_mod(&z_poly).unwrap();
+        let a_expr = (UPoly(vec![T::from(-1)]) * s0 * inv).div_rem(&z_poly).1;
+        let b_expr = (ident - UPoly(vec![T::from(t)]) * a_expr.clone()).div_rem(&z_poly).1;
+
+        return (z, a_expr, b_expr);
+    }
+
+    unreachable!()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{primitive_element, real_roots, Algebraic};
+    use crate::rational::Rat;
+    use crate::univariate::upoly;
+    use std::cmp::Ordering;
+
+    #[test]
+    fn sign_at() {
+        // sqrt(2)
+        let root = real_roots(&upoly(&[1, 0, -2])).pop().unwrap();
+
+        assert_eq!(Ordering::Equal, root.sign_at(&upoly(&[1, 0, -2])));
+        assert_eq!(Ordering::Equal, root.sign_at(&upoly(&[1, 0, -2, 0])));
+        assert_eq!(Ordering::Greater, root.sign_at(&upoly(&[1, -1])));
+        assert_eq!(Ordering::Less, root.sign_at(&upoly(&[2, -3])));
+    }
+
+    #[test]
+    fn compare() {
+        let sqrt2 = real_roots(&upoly(&[1, 0, -2])).pop().unwrap();
+        let sqrt2_again = real_roots(&upoly(&[1, -1, -2, 2])).pop().unwrap();
+        let sqrt3 = real_roots(&upoly(&[1, 0, -3])).pop().unwrap();
+
+        assert_eq!(Ordering::Equal, sqrt2.compare(&sqrt2_again));
+        assert_eq!(Ordering::Less, sqrt2.compare(&sqrt3));
+        assert_eq!(Ordering::Greater, sqrt3.compare(&Algebraic::from_point(Rat::from(1))));
+        assert_eq!(
+            Ordering::Equal,
+            real_roots(&upoly(&[2, -1, -1]))[1].compare(&Algebraic::from_point(Rat::from(1)))
+        );
+    }
+
+    #[test]
+    fn primitive() {
+        let sqrt2 = real_roots(&upoly(&[1, 0, -2])).pop().unwrap();
+        let sqrt3 = real_roots(&upoly(&[1, 0, -3])).pop().unwrap();
+
+        let (z, a, b) = primitive_element(&sqrt2, &sqrt3);
+
+        // a^2 - 2, b^2 - 3 and a * b - sqrt(6) all vanish at z
+        assert_eq!(Ordering::Equal, z.sign_at(&(a.clone() * a.clone() - upoly(&[2]))));
+        assert_eq!(Ordering::Equal, z.sign_at(&(b.clone() * b.clone() - upoly(&[3]))));
+        assert_eq!(Ordering::Greater, z.sign_at(&a));
+        assert_eq!(Ordering::Greater, z.sign_at(&b));
+
+        let ab = a * b;
+        assert_eq!(Ordering::Equal, z.sign_at(&(ab.clone() * ab - upoly(&[6]))));
+    }
+
+    #[test]
+    fn arithmetic() {
+        let sqrt2 = real_roots(&upoly(&[1, 0, -2])).pop().unwrap();
+        let sqrt3 = real_roots(&upoly(&[1, 0, -3])).pop().unwrap();
+        let sqrt6 = real_roots(&upoly(&[1, 0, -6])).pop().unwrap();
+        let rat = |n: i64, d: i64| Algebraic::from_point(Rat::from(n) / Rat::from(d));
+
+        // sqrt(2) + sqrt(3) is the largest root of x^4 - 10x^2 + 1
+        let sum = sqrt2.clone() + sqrt3.clone();
+        assert_eq!(real_roots(&upoly(&[1, 0, -10, 0, 1]))[3], sum);
+        assert_eq!(sqrt6, sqrt2.clone() * sqrt3.clone());
+        assert_eq!(rat(2, 1), sqrt2.clone() * sqrt2.clone());
+        assert_eq!(rat(0, 1), sqrt2.clone() - sqrt2.clone());
+
+        let neg = -sqrt2.clone();
+        assert_eq!(real_roots(&upoly(&[1, 0, -2]))[0], neg);
+        assert_eq!(0, neg.n);
+        assert_eq!(Ordering::Less, neg.sign());
+        assert_eq!(rat(0, 1), neg + sqrt2.clone());
+
+        // 1 / sqrt(2) = sqrt(2) / 2
+        assert_eq!(sqrt2.clone() * rat(1, 2), sqrt2.inv().unwrap());
+        assert_eq!(rat(4, 3), rat(3, 4).inv().unwrap());
+        assert!(rat(0, 1).inv().is_none());
+
+        assert!(sqrt2 < sqrt3.clone() && sqrt3 < sum);
+
+        // 2 - sqrt(2)^2 + 3 sqrt(2)
+        let val = sqrt2.eval(&upoly(&[-1, 3, 2]));
+        assert_eq!(sqrt2.clone() * rat(3, 1), val);
+    }
+
+    #[test]
+    fn minimal() {
+        // (x^2 - 2)(x - 1)(x + 3), whose largest root is sqrt(2)
+        let sqrt2 = real_roots(&upoly(&[1, 2, -5, -4, 6])).pop().unwrap().minimal().unwrap();
+        assert_eq!(upoly(&[1, 0, -2]), sqrt2.p);
+        assert_eq!(1, sqrt2.n);
+
+        let one = real_roots(&upoly(&[1, 2, -5, -4, 6]))[2].minimal().unwrap();
+        assert_eq!(Algebraic::from_point(Rat::from(1)), one);
+    }
+}
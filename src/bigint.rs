@@ -0,0 +1,472 @@
+use std::{
+    cmp::{Ord, Ordering, PartialOrd},
+    fmt, ops,
+};
+
+// arbitrary precision integer: little endian base 2^32 digits without leading zeros,
+// so zero has no digits and is never negative
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    neg: bool,
+    mag: Vec<u32>,
+}
+
+fn trim(mut mag: Vec<u32>) -> Vec<u32> {
+    while mag.last() == Some(&0) {
+        mag.pop();
+    }
+
+    mag
+}
+
+fn cmp_mag(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut sum = Vec::with_capacity(long.len() + 1);
+    let mut carry = 0u64;
+
+    for (i, digit) in long.iter().enumerate() {
+        let s = *digit as u64 + *short.get(i).unwrap_or(&0) as u64 + carry;
+        sum.push(s as u32);
+        carry = s >> 32;
+    }
+
+    sum.push(carry as u32);
+    trim(sum)
+}
+
+// a must not be smaller than b
+fn sub_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut diff = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+
+    for (i, digit) in a.iter().enumerate() {
+        let d = *digit as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+        diff.push(d as u32);
+        borrow = (d < 0) as i64;
+    }
+
+    trim(diff)
+}
+
+fn mul_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+
+    let mut prod = vec![0u32; a.len() + b.len()];
+
+    for (i, x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+
+        for (j, y) in b.iter().enumerate() {
+            let p = *x as u64 * *y as u64 + prod[i + j] as u64 + carry;
+            prod[i + j] = p as u32;
+            carry = p >> 32;
+        }
+
+        prod[i + b.len()] = carry as u32;
+    }
+
+    trim(prod)
+}
+
+fn divrem_small(a: &[u32], d: u32) -> (Vec<u32>, u32) {
+    let mut quot = vec![0u32; a.len()];
+    let mut rem = 0u64;
+
+    for i in (0..a.len()).rev() {
+        let cur = (rem << 32) | a[i] as u64;
+        quot[i] = (cur / d as u64) as u32;
+        rem = cur % d as u64;
+    }
+
+    (trim(quot), rem as u32)
+}
+
+fn shl_bits(a: &[u32], shift: u32) -> Vec<u32> {
+    if shift == 0 {
+        return a.to_vec();
+    }
+
+    let mut out = Vec::with_capacity(a.len() + 1);
+    let mut carry = 0u32;
+
+    for digit in a {
+        out.push((digit << shift) | carry);
+        carry = digit >> (32 - shift);
+    }
+
+    out.push(carry);
+    out
+}
+
+// Knuth's algorithm D
+fn divrem_mag(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if cmp_mag(a, b) == Ordering::Less {
+        return (vec![], a.to_vec());
+    }
+
+    if b.len() == 1 {
+        let (quot, rem) = divrem_small(a, b[0]);
+        return (quot, trim(vec![rem]));
+    }
+
+    // normalize so the divisor's top digit has its high bit set
+    let shift = b[b.len() - 1].leading_zeros();
+    let v = trim(shl_bits(b, shift));
+    let mut u = shl_bits(a, shift);
+    if u.len() == a.len() {
+        u.push(0);
+    }
+
+    let n = v.len();
+    let mut quot = vec![0u32; u.len() - n];
+
+    for j in (0..u.len() - n).rev() {
+        let top = ((u[j + n] as u64) << 32) | u[j + n - 1] as u64;
+        let mut qhat = top / v[n - 1] as u64;
+        let mut rhat = top % v[n - 1] as u64;
+
+        while qhat >> 32 != 0 || qhat * v[n - 2] as u64 > ((rhat << 32) | u[j + n - 2] as u64) {
+            qhat -= 1;
+            rhat += v[n - 1] as u64;
+
+            if rhat >> 32 != 0 {
+                break;
+            }
+        }
+
+        let mut borrow = 0i64;
+        let mut carry = 0u64;
+
+        for i in 0..n {
+            let p = qhat * v[i] as u64 + carry;
+            carry = p >> 32;
+
+            let t = u[i + j] as i64 - borrow - (p & 0xFFFF_FFFF) as i64;
+            u[i + j] = t as u32;
+            borrow = (t < 0) as i64;
+        }
+
+        let t = u[j + n] as i64 - borrow - carry as i64;
+        u[j + n] = t as u32;
+
+        // qhat was one too large
+        if t < 0 {
+            qhat -= 1;
+            let mut carry = 0u64;
+
+            for i in 0..n {
+                let s = u[i + j] as u64 + v[i] as u64 + carry;
+                u[i + j] = s as u32;
+                carry = s >> 32;
+            }
+
+            u[j + n] = u[j + n].wrapping_add(carry as u32);
+        }
+
+        quot[j] = qhat as u32;
+    }
+
+    let rem = if shift == 0 {
+        u[..n].to_vec()
+    } else {
+        (0..n)
+            .map(|i| (u[i] >> shift) | (u[i + 1] << (32 - shift)))
+            .collect()
+    };
+
+    (trim(quot), trim(rem))
+}
+
+impl BigInt {
+    fn from_parts(neg: bool, mag: Vec<u32>) -> Self {
+        let mag = trim(mag);
+        BigInt {
+            neg: neg && !mag.is_empty(),
+            mag,
+        }
+    }
+
+    pub fn zero() -> Self {
+        BigInt {
+            neg: false,
+            mag: vec![],
+        }
+    }
+
+    pub fn one() -> Self {
+        BigInt {
+            neg: false,
+            mag: vec![1],
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mag.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.neg
+    }
+
+    pub fn abs(&self) -> Self {
+        BigInt {
+            neg: false,
+            mag: self.mag.clone(),
+        }
+    }
+
+    // truncating division, so the remainder takes the sign of self
+    pub fn div_rem(&self, other: &BigInt) -> (BigInt, BigInt) {
+        if other.is_zero() {
+            panic!("integer division by zero");
+        }
+
+        let (quot, rem) = divrem_mag(&self.mag, &other.mag);
+
+        (
+            BigInt::from_parts(self.neg != other.neg, quot),
+            BigInt::from_parts(self.neg, rem),
+        )
+    }
+
+    // non-negative; zero only when both are zero
+    pub fn gcd(&self, other: &BigInt) -> BigInt {
+        let (mut a, mut b) = (self.abs(), other.abs());
+
+        while !b.is_zero() {
+            let (_, rem) = a.div_rem(&b);
+            a = b;
+            b = rem;
+        }
+
+        a
+    }
+
+    pub fn pow(&self, exp: u32) -> BigInt {
+        (0..exp).fold(BigInt::one(), |acc, _| acc * self.clone())
+    }
+
+    pub fn bits(&self) -> u64 {
+        match self.mag.last() {
+            Some(top) => self.mag.len() as u64 * 32 - top.leading_zeros() as u64,
+            None => 0,
+        }
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(val: i64) -> Self {
+        let abs = val.unsigned_abs();
+        BigInt::from_parts(val < 0, vec![abs as u32, (abs >> 32) as u32])
+    }
+}
+
+impl From<i128> for BigInt {
+    fn from(val: i128) -> Self {
+        let abs = val.unsigned_abs();
+        BigInt::from_parts(
+            val < 0,
+            (0..4).map(|i| (abs >> (32 * i)) as u32).collect(),
+        )
+    }
+}
+
+impl TryFrom<&BigInt> for i64 {
+    type Error = ();
+
+    fn try_from(val: &BigInt) -> Result<i64, Self::Error> {
+        if val.mag.len() > 2 {
+            return Err(());
+        }
+
+        let abs = val.mag.iter().rev().fold(0u64, |acc, d| (acc << 32) | *d as u64);
+
+        if val.neg {
+            0i64.checked_sub_unsigned(abs).ok_or(())
+        } else {
+            i64::try_from(abs).map_err(|_| ())
+        }
+    }
+}
+
+impl From<&BigInt> for f64 {
+    fn from(val: &BigInt) -> f64 {
+        let abs = val
+            .mag
+            .iter()
+            .rev()
+            .fold(0., |acc, d| acc * 4294967296. + *d as f64);
+
+        if val.neg {
+            -abs
+        } else {
+            abs
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &BigInt) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &BigInt) -> Ordering {
+        match (self.neg, other.neg) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_mag(&self.mag, &other.mag),
+            (true, true) => cmp_mag(&other.mag, &self.mag),
+        }
+    }
+}
+
+impl ops::Neg for BigInt {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        BigInt::from_parts(!self.neg, self.mag)
+    }
+}
+
+impl ops::Add<BigInt> for BigInt {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        if self.neg == rhs.neg {
+            return BigInt::from_parts(self.neg, add_mag(&self.mag, &rhs.mag));
+        }
+
+        match cmp_mag(&self.mag, &rhs.mag) {
+            Ordering::Less => BigInt::from_parts(rhs.neg, sub_mag(&rhs.mag, &self.mag)),
+            _ => BigInt::from_parts(self.neg, sub_mag(&self.mag, &rhs.mag)),
+        }
+    }
+}
+
+impl ops::Sub<BigInt> for BigInt {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl ops::Mul<BigInt> for BigInt {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        BigInt::from_parts(self.neg != rhs.neg, mul_mag(&self.mag, &rhs.mag))
+    }
+}
+
+impl ops::Div<BigInt> for BigInt {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        self.div_rem(&rhs).0
+    }
+}
+
+impl ops::Rem<BigInt> for BigInt {
+    type Output = Self;
+
+    fn rem(self, rhs: Self) -> Self {
+        self.div_rem(&rhs).1
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+
+        // base 10^9 chunks, least significant first
+        let mut chunks = vec![];
+        let mut mag = self.mag.clone();
+
+        while !mag.is_empty() {
+            let (quot, rem) = divrem_small(&mag, 1_000_000_000);
+            chunks.push(rem);
+            mag = quot;
+        }
+
+        if self.neg {
+            write!(f, "-")?;
+        }
+
+        write!(f, "{}", chunks.pop().unwrap())?;
+
+        for chunk in chunks.iter().rev() {
+            write!(f, "{chunk:09}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BigInt;
+    use rand::prelude::*;
+
+    #[test]
+    fn arith() {
+        let a = BigInt::from(i64::MAX) * BigInt::from(i64::MAX);
+        assert_eq!("85070591730234615847396907784232501249", a.to_string());
+
+        let b = a.clone() - BigInt::from(i128::MAX);
+        assert_eq!("-85070591730234615884290395931651604478", b.to_string());
+
+        let (quot, rem) = a.div_rem(&BigInt::from(-1_000_000_007i64));
+        assert_eq!("-85070591134740477904213562454", quot.to_string());
+        assert_eq!(a, quot * BigInt::from(-1_000_000_007i64) + rem.clone());
+        assert!(rem >= BigInt::zero());
+
+        assert_eq!(Ok(i64::MIN), i64::try_from(&BigInt::from(i64::MIN)));
+        assert_eq!(Err(()), i64::try_from(&(BigInt::from(i64::MAX) + BigInt::one())));
+        assert_eq!(BigInt::from(1i64 << 20), BigInt::from(2i64).pow(20));
+    }
+
+    #[test]
+    fn division_fuzz() {
+        let mut rng = SmallRng::seed_from_u64(1);
+
+        let random_big = |rng: &mut SmallRng| {
+            (0..rng.gen_range(1..6)).fold(BigInt::from(rng.next_u64() as i64), |acc, _| {
+                acc * BigInt::from(rng.next_u64() as i64) + BigInt::from(rng.gen_range(-9..9i64))
+            })
+        };
+
+        for _ in 0..500 {
+            let a = random_big(&mut rng);
+            let b = random_big(&mut rng);
+
+            let (quot, rem) = a.div_rem(&b);
+
+            assert_eq!(a, quot * b.clone() + rem.clone());
+            assert!(rem.abs() < b.abs());
+            assert!(rem.is_zero() || rem.is_negative() == a.is_negative());
+
+            let g = a.gcd(&b);
+            assert!((a.clone() % g.clone()).is_zero() && (b.clone() % g.clone()).is_zero());
+            assert_eq!(BigInt::one(), (a / g.clone()).gcd(&(b / g)));
+        }
+    }
+}
@@ -0,0 +1,304 @@
+use std::{
+    cmp::{Ord, Ordering, PartialOrd},
+    fmt, ops,
+};
+
+use crate::bigint::BigInt;
+use crate::field::{self, Fraction};
+use crate::rational::{Inexact, Rat};
+use crate::univariate::UPoly;
+
+// dense, ascending integer coefficients
+pub(crate) type IntPoly = Vec<BigInt>;
+
+// exact rational type of unbounded size; slower than Rat but never loses precision
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct BigRat {
+    pub num: BigInt,
+    pub den: BigInt,
+}
+
+impl BigRat {
+    // canonical form: positive denominator, no common factor, zero as 0/1
+    pub fn new(num: BigInt, den: BigInt) -> BigRat {
+        if den.is_zero() {
+            panic!("rational with zero denominator");
+        }
+
+        let g = num.gcd(&den);
+        let (num, den) = if g.is_zero() || g == BigInt::one() {
+            (num, den)
+        } else {
+            (num / g.clone(), den / g)
+        };
+
+        if num.is_zero() {
+            BigRat {
+                num,
+                den: BigInt::one(),
+            }
+        } else if den.is_negative() {
+            BigRat { num: -num, den: -den }
+        } else {
+            BigRat { num, den }
+        }
+    }
+
+    pub fn try_int(&self) -> Option<&BigInt> {
+        if self.den == BigInt::one() {
+            Some(&self.num)
+        } else {
+            None
+        }
+    }
+}
+
+impl PartialOrd<BigRat> for BigRat {
+    fn partial_cmp(&self, other: &BigRat) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigRat {
+    fn cmp(&self, other: &BigRat) -> Ordering {
+        (self.num.clone() * other.den.clone()).cmp(&(other.num.clone() * self.den.clone()))
+    }
+}
+
+impl From<i64> for BigRat {
+    fn from(val: i64) -> Self {
+        BigRat {
+            num: BigInt::from(val),
+            den: BigInt::one(),
+        }
+    }
+}
+
+impl From<Rat> for BigRat {
+    fn from(val: Rat) -> Self {
+        BigRat::new(BigInt::from(val.num), BigInt::from(val.den))
+    }
+}
+
+// fails when the numerator or denominator needs more than 64 bits
+impl TryFrom<BigRat> for Rat {
+    type Error = ();
+
+    fn try_from(val: BigRat) -> Result<Rat, Self::Error> {
+        let num = i64::try_from(&val.num)?;
+        let den = i64::try_from(&val.den)?;
+
+        Ok(Rat::from(num) / Rat::from(den))
+    }
+}
+
+// the same conversion, failing the way rounded Rat arithmetic reports it
+pub(crate) fn to_rat(val: BigRat) -> Result<Rat, Inexact> {
+    Rat::try_from(val).map_err(|_| Inexact)
+}
+
+// the coefficients of p, ascending, scaled by a positive constant to coprime integers
+pub(crate) fn int_coefs(p: &UPoly<BigRat>) -> IntPoly {
+    let scale = BigRat::primitive(&p.0);
+
+    p.0.iter()
+        .rev()
+        .map(|c| (c.clone() / scale.clone()).try_int().unwrap().clone())
+        .collect()
+}
+
+impl From<BigRat> for f64 {
+    fn from(val: BigRat) -> f64 {
+        // drop low bits evenly so both halves stay within f64 range
+        let excess = val.num.bits().max(val.den.bits()).saturating_sub(1000) as u32;
+        let scale = BigInt::from(2i64).pow(excess);
+
+        f64::from(&(val.num / scale.clone())) / f64::from(&(val.den / scale))
+    }
+}
+
+impl field::Zero for BigRat {
+    fn zero() -> Self {
+        BigRat::from(0)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.num.is_zero()
+    }
+}
+
+impl field::One for BigRat {
+    fn one() -> Self {
+        BigRat::from(1)
+    }
+}
+
+impl ops::Add<BigRat> for BigRat {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        if self.den == rhs.den {
+            return BigRat::new(self.num + rhs.num, self.den);
+        }
+
+        BigRat::new(
+            self.num * rhs.den.clone() + rhs.num * self.den.clone(),
+            self.den * rhs.den,
+        )
+    }
+}
+
+impl ops::Sub<BigRat> for BigRat {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + BigRat {
+            num: -rhs.num,
+            den: rhs.den,
+        }
+    }
+}
+
+impl ops::Mul<BigRat> for BigRat {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        BigRat::new(self.num * rhs.num, self.den * rhs.den)
+    }
+}
+
+impl ops::Mul<i64> for BigRat {
+    type Output = Self;
+
+    fn mul(self, rhs: i64) -> Self {
+        self * BigRat::from(rhs)
+    }
+}
+
+impl ops::Div<BigRat> for BigRat {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        if rhs.num.is_zero() {
+            panic!("rational division by zero");
+        }
+
+        BigRat::new(self.num * rhs.den, self.den * rhs.num)
+    }
+}
+
+impl fmt::Display for BigRat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.den == BigInt::one() {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+impl fmt::Debug for BigRat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self}")
+    }
+}
+
+impl field::Fraction for BigRat {
+    fn primitive(vals: &[Self]) -> Self {
+        let (num_gcd, den_lcm) = vals.iter().fold(
+            (BigInt::zero(), BigInt::one()),
+            |(num_gcd, den_lcm), val| {
+                let den_gcd = den_lcm.gcd(&val.den);
+                (num_gcd.gcd(&val.num), den_lcm / den_gcd * val.den.clone())
+            },
+        );
+
+        if num_gcd.is_zero() {
+            BigRat::from(1)
+        } else {
+            BigRat::new(num_gcd, den_lcm)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BigRat;
+    use crate::bigint::BigInt;
+    use crate::cad::resultant::resultant;
+    use crate::field::Fraction;
+    use crate::poly::Poly;
+    use crate::rational::Rat;
+    use std::cmp::Ordering;
+
+    #[test]
+    fn arith() {
+        let a = BigRat::from(3) / BigRat::from(-6);
+        assert_eq!("-1/2", a.to_string());
+
+        let big = BigRat::from(i64::MAX) * BigRat::from(i64::MAX);
+        assert_eq!("85070591730234615847396907784232501249", big.to_string());
+        assert_eq!(BigRat::from(i64::MAX), big.clone() / BigRat::from(i64::MAX));
+        assert_eq!(Ordering::Less, (big.clone() * -1).cmp(&a));
+
+        let third = BigRat::from(1) / BigRat::from(3);
+        assert_eq!(BigRat::from(1), third.clone() + third.clone() + third);
+        assert_eq!(BigRat::from(0), a.clone() - a);
+
+        // 9/2 and 3/8 are 12 and 1 times 3/8
+        assert_eq!(
+            BigRat::from(3) / BigRat::from(8),
+            BigRat::primitive(&[BigRat::from(9) / BigRat::from(2), BigRat::from(3) / BigRat::from(8)])
+        );
+    }
+
+    #[test]
+    fn rat_conversion() {
+        let r = Rat::from(-22) / Rat::from(7);
+        let big = BigRat::from(r);
+
+        assert_eq!(BigRat::new(BigInt::from(-22i64), BigInt::from(7i64)), big);
+        assert_eq!(Ok(r), Rat::try_from(big));
+
+        let too_big = BigRat::from(i64::MAX) * BigRat::from(2);
+        assert_eq!(Err(()), Rat::try_from(too_big.clone()));
+        assert_eq!(f64::from(too_big), 2. * i64::MAX as f64);
+    }
+
+    #[test]
+    fn exact_resultant() {
+        // res(x^2 - 10^10, x - 10^10) = 10^20 - 10^10 overflows i64
+        let n = 10_000_000_000;
+        let a: Poly<BigRat> = Poly::var(0, 2) - Poly::constant(BigRat::from(n));
+        let b: Poly<BigRat> = Poly::var(0, 1) - Poly::constant(BigRat::from(n));
+
+        let res = resultant(&a, &b, 0);
+
+        assert_eq!(
+            Poly::constant(BigRat::from(n) * BigRat::from(n) - BigRat::from(n)),
+            res
+        );
+    }
+
+    #[test]
+    fn exact_roots() {
+        use crate::algebraic::{real_roots, Algebraic};
+        use crate::univariate::UPoly;
+
+        // (x - 10^10)(x - 10^10 - 1), whose constant term is past i64
+        let n = BigRat::from(10_000_000_000);
+        let p = UPoly(vec![
+            BigRat::from(1),
+            (n.clone() * 2 + BigRat::from(1)) * -1,
+            n.clone() * (n.clone() + BigRat::from(1)),
+        ]);
+
+        let roots = real_roots(&p);
+        assert_eq!(2, roots.len());
+        assert_eq!(Ordering::Equal, roots[0].compare(&Algebraic::from_point(n.clone())));
+        assert_eq!(
+            Ordering::Equal,
+            roots[1].compare(&Algebraic::from_point(n + BigRat::from(1)))
+        );
+    }
+}
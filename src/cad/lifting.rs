@@ -0,0 +1,374 @@
+use std::cmp::Ordering;
+use std::hash::Hash;
+
+use crate::algebraic::{primitive_element, real_roots, Algebraic};
+use crate::cad::projection::projection;
+use crate::cad::resultant::resultant;
+use crate::field::{simplest_between, Field};
+use crate::interval::Interval;
+use crate::poly::Poly;
+use crate::univariate::{Root, UPoly};
+
+// a point whose coordinates all lie in Q(prim); coords[i] is a polynomial in prim
+// giving the value of vars[i], reduced modulo prim's defining polynomial
+#[derive(Debug, Clone)]
+pub struct Sample<T: Field> {
+    pub prim: Algebraic<T>,
+    pub vars: Vec<usize>,
+    pub coords: Vec<UPoly<T>>,
+    pub values: Vec<Algebraic<T>>,
+}
+
+impl<T: Field> Sample<T> {
+    pub fn origin() -> Self {
+        Sample {
+            prim: Algebraic::from_point(T::zero()),
+            vars: vec![],
+            coords: vec![],
+            values: vec![],
+        }
+    }
+
+    fn reduce(&self, p: UPoly<T>) -> UPoly<T> {
+        p.div_rem(&self.prim.p).1
+    }
+
+    // p with every sampled var replaced by its coordinate, as a polynomial in prim
+    pub fn substitute(&self, p: &Poly<T>) -> UPoly<T> {
+        let mut total = UPoly(vec![]);
+
+        for term in &p.terms {
+            let mut value = UPoly(vec![term.val.clone()]);
+
+            for (var, pow) in &term.vars {
+                let coord = match self.vars.iter().position(|v| v == var) {
+                    Some(i) => &self.coords[i],
+                    None => panic!("variable {} has no coordinate in this sample", var),
+                };
+
+                for _ in 0..*pow {
+                    value = self.reduce(value * coord.clone());
+                }
+            }
+
+            total = total + value;
+        }
+
+        total
+    }
+
+    pub fn sign(&self, p: &Poly<T>) -> Ordering {
+        self.prim.sign_at(&self.substitute(p))
+    }
+
+    pub fn with_rational(&self, var: usize, val: T) -> Self {
+        let mut next = self.clone();
+
+        next.vars.push(var);
+        next.coords.push(UPoly(vec![val.clone()]).trim());
+        next.values.push(Algebraic::from_point(val));
+
+        next
+    }
+
+    // var set to coord, a polynomial in prim
+    pub fn with_coord(&self, var: usize, coord: UPoly<T>) -> Self {
+        let coord = self.reduce(coord);
+        let mut next = self.clone();
+
+        next.vars.push(var);
+        next.values.push(self.prim.eval(&coord));
+        next.coords.push(coord);
+
+        next
+    }
+
+    pub fn with_section(&self, var: usize, val: &Algebraic<T>) -> Self {
+        if let Root::Point(x) = &val.val {
+            return self.with_rational(var, x.clone());
+        }
+
+        let (prim, old_expr, new_expr) = primitive_element(&self.prim, val);
+
+        // rewrite each coordinate as a polynomial in the new primitive element
+        let coords = self
+            .coords
+            .iter()
+            .map(|coord| {
+                coord.0.iter().fold(UPoly(vec![]), |acc, coef| {
+                    (acc * old_expr.clone() + UPoly(vec![coef.clone()]).trim())
+                        .div_rem(&prim.p)
+                        .1
+                })
+            })
+            .chain(std::iter::once(new_expr))
+            .collect();
+
+        let mut vars = self.vars.clone();
+        vars.push(var);
+
+        let mut values = self.values.clone();
+        values.push(val.clone());
+
+        Sample {
+            prim,
+            vars,
+            coords,
+            values,
+        }
+    }
+
+    // real roots of p in var over this sample, unsorted and possibly repeated
+    pub fn sections(&self, p: &Poly<T>, var: usize) -> Vec<Algebraic<T>> {
+        let mut coefs = p
+            .coefs(var)
+            .iter()
+            .map(|c| self.substitute(c))
+            .skip_while(|c| self.prim.sign_at(c) == Ordering::Equal)
+            .collect::<Vec<_>>();
+
+        if coefs.len() < 2 {
+            return vec![];
+        }
+
+        if let Root::Point(x) = &self.prim.val {
+            return real_roots(&UPoly(coefs.iter().map(|c| c.eval(x)).collect()));
+        }
+
+        // drop factors of the defining polynomial that divide every coef, so the norm cannot vanish
+        let common = coefs.iter().fold(self.prim.p.clone(), |acc, c| acc.gcd(c));
+        let min_poly = self.prim.p.div_rem(&common).0;
+
+        for c in &mut coefs {
+            *c = c.div_rem(&min_poly).1;
+        }
+
+        const X: usize = 0;
+        const Z: usize = 1;
+
+        let bivariate = Poly::from_uni_fmt(coefs.iter().map(|c| c.to_poly(Z)).collect(), X);
+        let norm = UPoly::from_poly(&resultant(&bivariate, &min_poly.to_poly(Z), Z), X);
+
+        // roots of the norm include roots over every conjugate of prim; keep the ones over prim
+        let prim_interval = self.prim.interval();
+        let coef_intervals = coefs
+            .iter()
+            .map(|c| c.eval_interval(&prim_interval))
+            .collect::<Vec<_>>();
+
+        real_roots(&norm)
+            .into_iter()
+            .filter(|root| {
+                let root_interval = root.interval();
+                let enclosure = coef_intervals
+                    .iter()
+                    .fold(Interval::point(T::zero()), |acc, c| {
+                        acc * root_interval.clone() + c.clone()
+                    });
+
+                enclosure.contains(&T::zero())
+                    && self.with_section(var, root).sign(p) == Ordering::Equal
+            })
+            .collect()
+    }
+
+    // the samples of every cell in the cylinder over this one, ordered along var:
+    // sectors at even positions and sections at odd positions
+    pub fn stack(&self, ps: &[Poly<T>], var: usize) -> Vec<Sample<T>> {
+        let mut roots = ps
+            .iter()
+            .flat_map(|p| self.sections(p, var))
+            .collect::<Vec<_>>();
+
+        roots.sort_by(|a, b| a.compare(b));
+        roots.dedup_by(|a, b| a.compare(b) == Ordering::Equal);
+
+        if roots.is_empty() {
+            return vec![self.with_rational(var, T::zero())];
+        }
+
+        // refine neighbours until their isolating intervals are disjoint
+        for i in 1..roots.len() {
+            while roots[i - 1].interval().hi >= roots[i].interval().lo {
+                roots[i - 1].refine();
+                roots[i].refine();
+            }
+        }
+
+        let lowest = roots[0].interval().lo;
+        let highest = roots[roots.len() - 1].interval().hi;
+
+        let mut stack =
+            vec![self.with_rational(var, simplest_between(&(lowest.clone() - T::one()), &lowest))];
+
+        for (i, root) in roots.iter().enumerate() {
+            stack.push(self.with_section(var, root));
+
+            let sector = match roots.get(i + 1) {
+                Some(next) => simplest_between(&root.interval().hi, &next.interval().lo),
+                None => simplest_between(&highest, &(highest.clone() + T::one())),
+            };
+
+            stack.push(self.with_rational(var, sector));
+        }
+
+        stack
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Cell<T: Field> {
+    // position in each stack, in lift order; even for sectors and odd for sections
+    pub index: Vec<usize>,
+    pub sample: Vec<Algebraic<T>>,
+    pub signs: Vec<Ordering>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Cad<T: Field> {
+    pub lift_order: Vec<usize>,
+    pub polys: Vec<Poly<T>>,
+    pub cells: Vec<Cell<T>>,
+}
+
+// projection factors by level in lift order; levels[i] holds polys in lift_order[..=i]
+pub struct Lifter<T: Field> {
+    pub lift_order: Vec<usize>,
+    pub levels: Vec<Vec<Poly<T>>>,
+}
+
+impl<T: Field + Hash> Lifter<T> {
+    // var_order is the projection order, so lifting starts from its last var
+    pub fn new(ps: &[Poly<T>], var_order: &[usize]) -> Self {
+        let levels = projection(ps.to_vec(), var_order)
+            .into_iter()
+            .zip(var_order)
+            .rev()
+            .map(|(level, var)| {
+                level
+                    .into_iter()
+                    .map(|coefs| Poly::from_uni_fmt(coefs, *var))
+                    .collect()
+            })
+            .collect();
+
+        Lifter {
+            lift_order: var_order.iter().rev().cloned().collect(),
+            levels,
+        }
+    }
+
+    pub fn stack(&self, sample: &Sample<T>) -> Vec<Sample<T>> {
+        let level = sample.vars.len();
+        sample.stack(&self.levels[level], self.lift_order[level])
+    }
+}
+
+pub fn cad<T: Field + Hash>(ps: Vec<Poly<T>>, var_order: &[usize]) -> Cad<T> {
+    let lifter = Lifter::new(&ps, var_order);
+    let mut cells = vec![];
+
+    fn lift<T: Field + Hash>(
+        lifter: &Lifter<T>,
+        ps: &[Poly<T>],
+        sample: Sample<T>,
+        index: &mut Vec<usize>,
+        cells: &mut Vec<Cell<T>>,
+    ) {
+        if sample.vars.len() == lifter.lift_order.len() {
+            cells.push(Cell {
+                index: index.clone(),
+                signs: ps.iter().map(|p| sample.sign(p)).collect(),
+                sample: sample.values,
+            });
+
+            return;
+        }
+
+        for (i, next) in lifter.stack(&sample).into_iter().enumerate() {
+            index.push(i);
+            lift(lifter, ps, next, index, cells);
+            index.pop();
+        }
+    }
+
+    lift(&lifter, &ps, Sample::origin(), &mut vec![], &mut cells);
+
+    Cad {
+        lift_order: lifter.lift_order,
+        polys: ps,
+        cells,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::cad;
+    use crate::poly::Poly;
+    use crate::rational::Rat;
+    use crate::univariate::UPoly;
+    use std::cmp::Ordering::*;
+
+    fn x(pow: u64) -> Poly<Rat> {
+        Poly::var(0, pow)
+    }
+
+    fn y(pow: u64) -> Poly<Rat> {
+        Poly::var(1, pow)
+    }
+
+    fn c(val: i64) -> Poly<Rat> {
+        Poly::constant(Rat::from(val))
+    }
+
+    #[test]
+    fn line() {
+        let decomp = cad(vec![x(2) - c(2)], &[0]);
+
+        let signs = decomp
+            .cells
+            .iter()
+            .map(|cell| cell.signs[0])
+            .collect::<Vec<_>>();
+        assert_eq!(signs, vec![Greater, Equal, Less, Equal, Greater]);
+    }
+
+    #[test]
+    fn circle() {
+        let decomp = cad(vec![x(2) + y(2) - c(1)], &[1, 0]);
+
+        assert_eq!(decomp.lift_order, vec![0, 1]);
+        assert_eq!(decomp.cells.len(), 13);
+
+        let inside = decomp
+            .cells
+            .iter()
+            .filter(|cell| cell.signs[0] == Less)
+            .map(|cell| cell.index.clone())
+            .collect::<Vec<_>>();
+
+        assert_eq!(inside, vec![vec![2, 2]]);
+    }
+
+    #[test]
+    fn irrational_fiber() {
+        // y^2 = x over x = sqrt(2) needs the quartic root 2^(1/4)
+        let decomp = cad(vec![x(2) - c(2), y(2) - x(1)], &[1, 0]);
+
+        assert_eq!(decomp.cells.len(), 21);
+
+        let cell = decomp
+            .cells
+            .iter()
+            .find(|cell| cell.index == vec![5, 1])
+            .unwrap();
+        assert_eq!(cell.signs, vec![Equal, Equal]);
+
+        let quartic = UPoly(vec![1, 0, 0, 0, -2].into_iter().map(Rat::from).collect());
+        assert_eq!(Equal, cell.sample[1].sign_at(&quartic));
+        assert_eq!(
+            Less,
+            cell.sample[1].sign_at(&UPoly(vec![Rat::from(1), Rat::from(0)]))
+        );
+    }
+}
@@ -1,3 +1,4 @@
+pub mod lifting;
 pub mod projection;
 pub mod resultant;
 pub mod tarski;
@@ -1,9 +1,11 @@
 use std::collections::HashSet;
 use std::hash::Hash;
 
+use crate::cad::resultant::principal_subresultant_coefs;
 use crate::field::Field;
 use crate::poly::Poly;
 
+// nonzero reducta of p in var, each densely represented with a nonzero leading coef
 pub fn reducta_set<T: Field + Hash>(p: &Poly<T>, var: usize) -> HashSet<Vec<Poly<T>>> {
     let coefs = p.coefs(var);
 
@@ -14,38 +16,72 @@ pub fn reducta_set<T: Field + Hash>(p: &Poly<T>, var: usize) -> HashSet<Vec<Poly
     let mut first_nonzero = coefs.len() - 1;
 
     while coefs[first_nonzero].is_zero() {
+        if first_nonzero == 0 {
+            return HashSet::new();
+        }
+
         first_nonzero -= 1;
     }
 
     (0..=first_nonzero)
-        .map(|i| coefs[i..=first_nonzero].to_vec())
+        .filter(|i| !coefs[*i].is_zero())
+        .map(|i| coefs[i..].to_vec())
         .collect()
 }
 
+// Collins' projection: level i holds the projection factors in var_order[i],
+// densely represented with coefs in var_order[i + 1..], so the last level is univariate
 pub fn projection<T: Field + Hash>(ps: Vec<Poly<T>>, var_order: &[usize]) -> Vec<HashSet<Vec<Poly<T>>>> {
     let mut projs = vec![HashSet::new()];
     for poly in ps {
         projs[0].insert(poly.coefs(var_order[0]));
     }
 
-    // for var in var_order.iter().skip(1) {
-    //     let mut next = HashSet::new();
-
-    //     for p in &projs[projs.len() - 1] {
-    //         // for red in reducta_set(p, var) {
+    for (level, var) in var_order.iter().enumerate().skip(1) {
+        let prev_var = var_order[level - 1];
+
+        let reducta = projs[level - 1]
+            .iter()
+            .map(|p| {
+                reducta_set(&Poly::from_uni_fmt(p.clone(), prev_var), prev_var)
+                    .into_iter()
+                    .map(|r| Poly::from_uni_fmt(r, prev_var))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut factors = vec![];
+
+        for (i, rs) in reducta.iter().enumerate() {
+            for r in rs {
+                factors.push(r.coefs(prev_var).swap_remove(0));
+                factors.extend(principal_subresultant_coefs(r, &r.derivative(prev_var), prev_var));
+            }
+
+            for other_rs in &reducta[i + 1..] {
+                for r in rs {
+                    for other_r in other_rs {
+                        factors.extend(principal_subresultant_coefs(r, other_r, prev_var));
+                    }
+                }
+            }
+        }
 
-    //         // }
-    //     }
+        let next = factors
+            .into_iter()
+            .filter(|p| p.terms.iter().any(|term| !term.vars.is_empty()))
+            .map(|p| p.monic().coefs(*var))
+            .collect();
 
-    //     projs.push(next);
-    // }
+        projs.push(next);
+    }
 
     projs
 }
 
 #[cfg(test)]
 mod tests {
-    use super::reducta_set;
+    use super::{projection, reducta_set};
     use crate::system;
 
     #[test]
@@ -71,4 +107,62 @@ mod tests {
             assert!(rs.contains(eps));
         }
     }
+
+    #[test]
+    fn red_trailing_zeros() {
+        let sys = system! {
+            x^3 + x,
+            1,
+        };
+
+        let rs = reducta_set(&sys.members[0], 0);
+
+        assert_eq!(rs.len(), 2);
+        assert!(rs.contains(&sys.members[0].coefs(0)));
+        assert!(rs.contains(&vec![sys.members[1].clone(), sys.constant(0)]));
+    }
+
+    #[test]
+    fn circle_and_line() {
+        let sys = system! {
+            x^2 + y^2 - 1,
+            x - y
+        };
+
+        let projs = projection(sys.members.clone(), &[0, 1]);
+
+        assert_eq!(projs.len(), 2);
+
+        let mut level_1 = projs[1]
+            .iter()
+            .map(|p| format!("{:?}", p.iter().map(|c| c.format(&sys.var_dict)).collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+
+        level_1.sort();
+
+        assert_eq!(
+            vec![
+                "[\"1\", \"0\", \"-0.5\"]",
+                "[\"1\", \"0\", \"-1\"]",
+                "[\"1\", \"0\"]",
+            ],
+            level_1
+        );
+    }
+
+    #[test]
+    fn sphere() {
+        let sys = system! {
+            x^2 + y^2 + z^2 - 1
+        };
+
+        let projs = projection(sys.members.clone(), &[0, 1, 2]);
+
+        assert_eq!(projs.len(), 3);
+        let circle = sys.var("y", 2) + sys.var("z", 2) - sys.constant(1);
+        assert!(projs[1].contains(&circle.coefs(1)));
+
+        assert_eq!(projs[2].len(), 1);
+        assert!(projs[2].contains(&(sys.var("z", 2) - sys.constant(1)).coefs(2)));
+    }
 }
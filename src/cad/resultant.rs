@@ -2,6 +2,7 @@ use std::collections::VecDeque;
 
 use crate::field::Field;
 use crate::poly::Poly;
+use crate::rational::{exact, Inexact, Rat};
 
 // Bareiss algorithm
 fn determinant<T: Field>(mut mat: Vec<Vec<Poly<T>>>, size: usize) -> Poly<T> {
@@ -39,7 +40,7 @@ fn determinant<T: Field>(mut mat: Vec<Vec<Poly<T>>>, size: usize) -> Poly<T> {
 
 // k'th order Sylvester matrix
 // see https://link.springer.com/article/10.1007/s00200-004-0158-4
-fn syl_k<T: Field>(a_coefs: &Vec<Poly<T>>, b_coefs: &Vec<Poly<T>>, k: usize) -> Vec<Vec<Poly<T>>> {
+fn syl_k<T: Field>(a_coefs: &[Poly<T>], b_coefs: &[Poly<T>], k: usize) -> Vec<Vec<Poly<T>>> {
     let mut rows = vec![];
     let a_deg = a_coefs.len() - 1;
     let b_deg = b_coefs.len() - 1;
@@ -105,12 +106,48 @@ pub fn subresultants<T: Field>(a: &Poly<T>, b: &Poly<T>, var: usize) -> Vec<Vec<
     srs
 }
 
+// subresultants over Rat, failing if any coefficient had to be rounded
+pub fn try_subresultants(a: &Poly<Rat>, b: &Poly<Rat>, var: usize) -> Result<Vec<Vec<Poly<Rat>>>, Inexact> {
+    exact(|| subresultants(a, b, var))
+}
+
+// eliminates var; it vanishes exactly when a and b have a common root in var
+pub fn resultant<T: Field>(a: &Poly<T>, b: &Poly<T>, var: usize) -> Poly<T> {
+    let (a_deg, b_deg) = (a.deg(var), b.deg(var));
+
+    if a_deg == 0 || b_deg == 0 {
+        let (base, exp) = if a_deg == 0 { (a, b_deg) } else { (b, a_deg) };
+
+        return (0..exp).fold(Poly::constant(T::one()), |acc, _| acc.mul_ref(base));
+    }
+
+    if a_deg >= b_deg {
+        subresultants(a, b, var).pop().unwrap().swap_remove(0)
+    } else if a_deg % 2 == 1 && b_deg % 2 == 1 {
+        subresultants(b, a, var).pop().unwrap().swap_remove(0) * Poly::constant(T::from(-1))
+    } else {
+        subresultants(b, a, var).pop().unwrap().swap_remove(0)
+    }
+}
+
+// psc_{min(deg a, deg b) - 1}, ..., psc_0 in var; argument order does not matter
+pub fn principal_subresultant_coefs<T: Field>(a: &Poly<T>, b: &Poly<T>, var: usize) -> Vec<Poly<T>> {
+    let (a, b) = if a.deg(var) >= b.deg(var) { (a, b) } else { (b, a) };
+
+    subresultants(a, b, var)
+        .into_iter()
+        .skip(2)
+        .map(|mut s| s.swap_remove(0))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{determinant, subresultants, syl_k};
+    use super::{determinant, subresultants, syl_k, try_subresultants};
     use crate::system;
 
     #[test]
+    #[allow(clippy::useless_vec)]
     fn sylvester() {
         let a_coefs = system! { 5, 4, 3, 2, 1 }.members;
         let b_coefs = system! { 4, 3, 2, 1 }.members;
@@ -197,4 +234,17 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn inexact() {
+        let sys = system! { x^2 - 3, x - 2 };
+        assert!(try_subresultants(&sys.get(0), &sys.get(1), 0).is_ok());
+
+        // the resultant 10^20 - 10^10 does not fit
+        let big = sys.constant(10_000_000_000);
+        let a = sys.var("x", 2) - big.clone();
+        let b = sys.var("x", 1) - big;
+
+        assert!(try_subresultants(&a, &b, 0).is_err());
+    }
 }
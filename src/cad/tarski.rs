@@ -1,11 +1,87 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::algebraic::Algebraic;
+use crate::bigrat::BigRat;
+use crate::cad::lifting::{Lifter, Sample};
+use crate::parse::{ParseError, Parser};
 use crate::poly::Poly;
 use crate::rational::Rat;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Cmp {
     Gt,
     Eq,
     Lt,
+    Ge,
+    Le,
+    Ne,
+}
+
+impl Cmp {
+    pub fn holds(&self, sign: Ordering) -> bool {
+        self.mask() & Cmp::from_sign(sign).mask() != 0
+    }
+
+    fn from_sign(sign: Ordering) -> Self {
+        match sign {
+            Ordering::Greater => Cmp::Gt,
+            Ordering::Equal => Cmp::Eq,
+            Ordering::Less => Cmp::Lt,
+        }
+    }
+
+    // the signs accepted, as bits for less, equal and greater
+    fn mask(&self) -> u8 {
+        match self {
+            Cmp::Lt => 0b001,
+            Cmp::Eq => 0b010,
+            Cmp::Gt => 0b100,
+            Cmp::Le => 0b011,
+            Cmp::Ne => 0b101,
+            Cmp::Ge => 0b110,
+        }
+    }
+
+    // none when no sign or every sign is accepted
+    fn from_mask(mask: u8) -> Option<Self> {
+        match mask {
+            0b001 => Some(Cmp::Lt),
+            0b010 => Some(Cmp::Eq),
+            0b100 => Some(Cmp::Gt),
+            0b011 => Some(Cmp::Le),
+            0b101 => Some(Cmp::Ne),
+            0b110 => Some(Cmp::Ge),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Cmp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = match self {
+            Cmp::Gt => ">",
+            Cmp::Eq => "=",
+            Cmp::Lt => "<",
+            Cmp::Ge => ">=",
+            Cmp::Le => "<=",
+            Cmp::Ne => "!=",
+        };
+
+        write!(f, "{symbol}")
+    }
+}
+
+// the signs of the projection factors and their derivatives still leave a true cell and a
+// false cell indistinguishable, so no solution formula over them exists
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Inseparable;
+
+impl fmt::Display for Inseparable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "sign conditions on the projection factors cannot express the solution set")
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -20,8 +96,77 @@ pub enum T {
     Or(Box<T>, Box<T>),
     Not(Box<T>),
     C(Constraint),
+    True,
+    False,
+}
+
+impl T {
+    // truth value given the sign of every constraint's polynomial
+    pub fn eval(&self, signs: &HashMap<Poly<Rat>, Ordering>) -> bool {
+        match self {
+            T::And(lhs, rhs) => lhs.eval(signs) && rhs.eval(signs),
+            T::Or(lhs, rhs) => lhs.eval(signs) || rhs.eval(signs),
+            T::Not(inner) => !inner.eval(signs),
+            T::C(c) => c.cmp_zero.holds(signs[&c.value]),
+            T::True => true,
+            T::False => false,
+        }
+    }
+
+    pub fn polys(&self) -> Vec<Poly<Rat>> {
+        let mut polys = vec![];
+
+        fn collect(t: &T, polys: &mut Vec<Poly<Rat>>) {
+            match t {
+                T::And(lhs, rhs) | T::Or(lhs, rhs) => {
+                    collect(lhs, polys);
+                    collect(rhs, polys);
+                }
+                T::Not(inner) => collect(inner, polys),
+                T::C(c) => {
+                    if !polys.contains(&c.value) {
+                        polys.push(c.value.clone());
+                    }
+                }
+                T::True | T::False => (),
+            }
+        }
+
+        collect(self, &mut polys);
+        polys
+    }
+
+    pub fn format(&self, var_dict: &[String]) -> String {
+        match self {
+            T::And(lhs, rhs) => {
+                let wrap = |t: &T| match t {
+                    T::Or(_, _) => format!("({})", t.format(var_dict)),
+                    _ => t.format(var_dict),
+                };
+
+                format!("{} and {}", wrap(lhs), wrap(rhs))
+            }
+            T::Or(lhs, rhs) => format!("{} or {}", lhs.format(var_dict), rhs.format(var_dict)),
+            T::Not(inner) => format!("not ({})", inner.format(var_dict)),
+            // exact coefficients, since Rat prints through f64
+            T::C(c) => {
+                let value = c.value.map(|val| BigRat::from(*val));
+                format!("{} {} 0", value.format(var_dict), c.cmp_zero)
+            }
+            T::True => "true".to_string(),
+            T::False => "false".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Sat {
+    // a value for every free and existential variable, by name
+    Sat(HashMap<String, Algebraic<Rat>>),
+    Unsat,
 }
 
+// exists x_1..x_n. forall y_1..y_m. data, over the remaining free variables
 #[derive(Debug, Clone)]
 pub struct Tarski {
     pub var_dict: Vec<String>,
@@ -29,3 +174,520 @@ pub struct Tarski {
     pub forall: Vec<usize>,
     pub data: T,
 }
+
+impl fmt::Display for Tarski {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (keyword, vars) in [("exists", &self.exists), ("forall", &self.forall)] {
+            if !vars.is_empty() {
+                let names = vars.iter().map(|v| self.var_dict[*v].as_str()).collect::<Vec<_>>();
+                write!(f, "{keyword} {}. ", names.join(", "))?;
+            }
+        }
+
+        write!(f, "{}", self.data.format(&self.var_dict))
+    }
+}
+
+impl Tarski {
+    // e.g. "exists x. forall y. x^2 + y > 0 or y <= 3"
+    pub fn parse(src: &str) -> Result<Tarski, ParseError> {
+        Parser::new(src, vec![])?.tarski()
+    }
+
+    // variables that occur in the formula without being quantified, by index
+    pub fn free(&self) -> Vec<usize> {
+        let mut free = self
+            .data
+            .polys()
+            .iter()
+            .flat_map(|p| p.terms.iter().flat_map(|term| term.vars.iter().map(|(var, _)| *var)))
+            .filter(|var| !self.exists.contains(var) && !self.forall.contains(var))
+            .collect::<Vec<_>>();
+
+        free.sort();
+        free.dedup();
+        free
+    }
+
+    // free vars first, so the quantified levels are lifted over each free cell
+    fn lift_order(&self) -> Vec<usize> {
+        let mut lift_order = self.free();
+        lift_order.extend(&self.exists);
+        lift_order.extend(&self.forall);
+        lift_order
+    }
+
+    fn truth(&self, lifter: &Lifter<Rat>, polys: &[Poly<Rat>], sample: &Sample<Rat>) -> bool {
+        let depth = sample.vars.len();
+
+        if depth == lifter.lift_order.len() {
+            let signs = polys.iter().map(|p| (p.clone(), sample.sign(p))).collect();
+            return self.data.eval(&signs);
+        }
+
+        let stack = lifter.stack(sample);
+
+        if depth < lifter.lift_order.len() - self.forall.len() {
+            stack.iter().any(|next| self.truth(lifter, polys, next))
+        } else {
+            stack.iter().all(|next| self.truth(lifter, polys, next))
+        }
+    }
+
+    // a quantifier-free formula over the free variables, equivalent to self; fails only when
+    // even derivatives of the projection factors cannot separate true cells from false ones,
+    // which needs two or more free variables
+    pub fn eliminate(&self) -> Result<T, Inseparable> {
+        let polys = self.data.polys();
+        let lift_order = self.lift_order();
+        let n_free = lift_order.len() - self.exists.len() - self.forall.len();
+
+        if lift_order.is_empty() {
+            let signs = polys.iter().map(|p| (p.clone(), Sample::origin().sign(p))).collect();
+            return Ok(if self.data.eval(&signs) { T::True } else { T::False });
+        }
+
+        let var_order = lift_order.iter().rev().cloned().collect::<Vec<_>>();
+        let mut extra: Vec<Poly<Rat>> = vec![];
+
+        loop {
+            let inputs = polys.iter().chain(&extra).cloned().collect::<Vec<_>>();
+            let lifter = Lifter::new(&inputs, &var_order);
+
+            let mut free_cells = vec![Sample::origin()];
+            for _ in 0..n_free {
+                free_cells = free_cells.iter().flat_map(|s| lifter.stack(s)).collect();
+            }
+
+            let truths = free_cells
+                .iter()
+                .map(|s| self.truth(&lifter, &polys, s))
+                .collect::<Vec<_>>();
+
+            if truths.iter().all(|t| *t) {
+                return Ok(T::True);
+            } else if truths.iter().all(|t| !*t) {
+                return Ok(T::False);
+            }
+
+            // projection factors in the free vars are sign-invariant on every free cell
+            let factors = lifter.levels[..n_free]
+                .iter()
+                .flatten()
+                .filter(|p| p.terms.iter().any(|term| !term.vars.is_empty()))
+                .cloned()
+                .collect::<Vec<_>>();
+
+            let sign_vectors = free_cells
+                .iter()
+                .map(|s| factors.iter().map(|p| s.sign(p)).collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+
+            let (true_vectors, false_vectors): (Vec<_>, Vec<_>) =
+                sign_vectors.into_iter().zip(&truths).partition(|(_, t)| **t);
+
+            let true_vectors = true_vectors.into_iter().map(|(v, _)| v).collect::<Vec<_>>();
+            let false_vectors = false_vectors.into_iter().map(|(v, _)| v).collect::<Vec<_>>();
+
+            if true_vectors.iter().all(|v| !false_vectors.contains(v)) {
+                return Ok(solution_formula(&factors, &true_vectors, &false_vectors));
+            }
+
+            // the factors' signs do not separate the cells; refine the decomposition
+            // with their derivatives, which suffices in the univariate case by Thom's lemma
+            let mut grew = false;
+
+            for (level, var) in lift_order[..n_free].iter().enumerate() {
+                for p in &lifter.levels[level] {
+                    let d = p.derivative(*var);
+
+                    if d.terms.iter().any(|term| !term.vars.is_empty()) && !extra.contains(&d) {
+                        extra.push(d);
+                        grew = true;
+                    }
+                }
+            }
+
+            if !grew {
+                return Err(Inseparable);
+            }
+        }
+    }
+
+    // free variables are treated as existential; stops at the first cell where the formula holds
+    pub fn check_sat(&self) -> Sat {
+        let polys = self.data.polys();
+        let lift_order = self.lift_order();
+        let n_witness = lift_order.len() - self.forall.len();
+
+        if lift_order.is_empty() {
+            let signs = polys.iter().map(|p| (p.clone(), Sample::origin().sign(p))).collect();
+            return if self.data.eval(&signs) { Sat::Sat(HashMap::new()) } else { Sat::Unsat };
+        }
+
+        let var_order = lift_order.iter().rev().cloned().collect::<Vec<_>>();
+        let lifter = Lifter::new(&polys, &var_order);
+
+        fn search(
+            formula: &Tarski,
+            lifter: &Lifter<Rat>,
+            polys: &[Poly<Rat>],
+            n_witness: usize,
+            sample: &Sample<Rat>,
+        ) -> Option<Sample<Rat>> {
+            if sample.vars.len() == n_witness {
+                return formula.truth(lifter, polys, sample).then(|| sample.clone());
+            }
+
+            let stack = lifter.stack(sample);
+
+            // sectors first, since their samples are rational
+            stack
+                .iter()
+                .step_by(2)
+                .chain(stack.iter().skip(1).step_by(2))
+                .find_map(|next| search(formula, lifter, polys, n_witness, next))
+        }
+
+        match search(self, &lifter, &polys, n_witness, &Sample::origin()) {
+            Some(sample) => Sat::Sat(
+                sample
+                    .vars
+                    .iter()
+                    .map(|var| self.var_dict[*var].clone())
+                    .zip(sample.values)
+                    .collect(),
+            ),
+            None => Sat::Unsat,
+        }
+    }
+
+    // the truth value of a sentence; none when free variables remain
+    pub fn decide(&self) -> Option<bool> {
+        if !self.free().is_empty() {
+            return None;
+        }
+
+        // with no free variables there is a single cell, so elimination cannot fail
+        match self.eliminate() {
+            Ok(T::True) => Some(true),
+            Ok(T::False) => Some(false),
+            _ => unreachable!(),
+        }
+    }
+}
+
+// a disjunction of sign conditions, one per true cell, each dropping the literals
+// that are not needed to exclude every false cell
+fn solution_formula(
+    factors: &[Poly<Rat>],
+    true_vectors: &[Vec<Ordering>],
+    false_vectors: &[Vec<Ordering>],
+) -> T {
+    let mut conjunctions: Vec<Vec<(usize, Cmp)>> = vec![];
+
+    for v in true_vectors {
+        let mut literals = (0..factors.len()).collect::<Vec<_>>();
+
+        for i in 0..factors.len() {
+            let without = literals.iter().filter(|j| **j != i).cloned().collect::<Vec<_>>();
+
+            if false_vectors.iter().all(|f| without.iter().any(|j| f[*j] != v[*j])) {
+                literals = without;
+            }
+        }
+
+        let conjunction = literals
+            .into_iter()
+            .map(|j| (j, Cmp::from_sign(v[j])))
+            .collect::<Vec<_>>();
+
+        if !conjunctions.contains(&conjunction) {
+            conjunctions.push(conjunction);
+        }
+    }
+
+    // (a and p > 0) or (a and p = 0) is a and p >= 0
+    'merge: loop {
+        for i in 0..conjunctions.len() {
+            for j in i + 1..conjunctions.len() {
+                let (lhs, rhs) = (&conjunctions[i], &conjunctions[j]);
+
+                if lhs.len() != rhs.len() || lhs.iter().zip(rhs).any(|(a, b)| a.0 != b.0) {
+                    continue;
+                }
+
+                let differing = (0..lhs.len()).filter(|k| lhs[*k].1 != rhs[*k].1).collect::<Vec<_>>();
+
+                if let [k] = differing[..] {
+                    let mut merged = lhs.clone();
+
+                    match Cmp::from_mask(lhs[k].1.mask() | rhs[k].1.mask()) {
+                        Some(cmp) => merged[k].1 = cmp,
+                        None => {
+                            merged.remove(k);
+                        }
+                    }
+
+                    conjunctions.swap_remove(j);
+
+                    if conjunctions.contains(&merged) {
+                        conjunctions.swap_remove(i);
+                    } else {
+                        conjunctions[i] = merged;
+                    }
+
+                    continue 'merge;
+                }
+            }
+        }
+
+        break;
+    }
+
+    conjunctions
+        .into_iter()
+        .map(|conjunction| {
+            conjunction
+                .into_iter()
+                .map(|(j, cmp_zero)| {
+                    T::C(Constraint {
+                        value: factors[j].clone(),
+                        cmp_zero,
+                    })
+                })
+                .reduce(|acc, c| T::And(Box::new(acc), Box::new(c)))
+                .unwrap_or(T::True)
+        })
+        .reduce(|acc, c| T::Or(Box::new(acc), Box::new(c)))
+        .unwrap_or(T::False)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cmp, Constraint, Sat, Tarski, T};
+    use crate::algebraic::Algebraic;
+    use crate::poly::Poly;
+    use crate::rational::Rat;
+    use crate::system;
+    use std::cmp::Ordering;
+    use std::collections::HashMap;
+
+    fn c(value: Poly<Rat>, cmp_zero: Cmp) -> T {
+        T::C(Constraint { value, cmp_zero })
+    }
+
+    // truth of a quantifier-free formula in x at a rational point
+    fn holds_at(t: &T, x: i64) -> bool {
+        let signs = t
+            .polys()
+            .into_iter()
+            .map(|p| {
+                let val = p.eval(0, Rat::from(x)).terms.first().map_or(Rat::from(0), |m| m.val);
+                (p, val.cmp(&Rat::from(0)))
+            })
+            .collect::<HashMap<_, Ordering>>();
+
+        t.eval(&signs)
+    }
+
+    #[test]
+    fn comparisons() {
+        let signs = [Ordering::Less, Ordering::Equal, Ordering::Greater];
+        let accepted = |cmp: Cmp| signs.iter().map(|s| cmp.holds(*s)).collect::<Vec<_>>();
+
+        assert_eq!(vec![false, true, true], accepted(Cmp::Ge));
+        assert_eq!(vec![true, true, false], accepted(Cmp::Le));
+        assert_eq!(vec![true, false, true], accepted(Cmp::Ne));
+
+        let sys = system! { x^2 + y, x - y };
+
+        let formula = Tarski {
+            var_dict: sys.var_dict.to_vec(),
+            exists: vec![0],
+            forall: vec![1],
+            data: T::Or(
+                Box::new(T::And(
+                    Box::new(c(sys.get(0), Cmp::Gt)),
+                    Box::new(T::Not(Box::new(c(sys.get(1), Cmp::Eq)))),
+                )),
+                Box::new(c(sys.get(1), Cmp::Le)),
+            ),
+        };
+
+        assert_eq!(
+            "exists x. forall y. x^2 + y > 0 and not (x - y = 0) or x - y <= 0",
+            formula.to_string()
+        );
+    }
+
+    #[test]
+    fn project_disc() {
+        let sys = system! { x^2 + y^2 - 1 };
+
+        let formula = Tarski {
+            var_dict: sys.var_dict.to_vec(),
+            exists: vec![1],
+            forall: vec![],
+            data: c(sys.get(0), Cmp::Lt),
+        };
+
+        let qf = formula.eliminate().unwrap();
+        assert_eq!("x^2 - 1 < 0", qf.format(&formula.var_dict));
+
+        assert_eq!(
+            vec![false, false, true, false, false],
+            [-2, -1, 0, 1, 2].iter().map(|x| holds_at(&qf, *x)).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn square_root_exists() {
+        let sys = system! { y^2 - x, x*y - 1 };
+
+        let formula = Tarski {
+            var_dict: sys.var_dict.to_vec(),
+            exists: vec![1],
+            forall: vec![],
+            data: c(sys.get(0), Cmp::Eq),
+        };
+
+        let qf = formula.eliminate().unwrap();
+        assert_eq!("x >= 0", qf.format(&formula.var_dict));
+
+        assert_eq!(
+            vec![false, true, true],
+            [-1, 0, 2].iter().map(|x| holds_at(&qf, *x)).collect::<Vec<_>>()
+        );
+
+        let formula = Tarski { data: c(sys.get(1), Cmp::Eq), ..formula };
+        let qf = formula.eliminate().unwrap();
+        assert_eq!("x != 0", qf.format(&formula.var_dict));
+
+        assert_eq!(
+            vec![true, false, true],
+            [-1, 0, 2].iter().map(|x| holds_at(&qf, *x)).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn sentences() {
+        let sys = system! { x^2 + 1, x^2 - y, x*y };
+
+        let positive = Tarski {
+            var_dict: sys.var_dict.to_vec(),
+            exists: vec![],
+            forall: vec![0],
+            data: c(sys.get(0), Cmp::Gt),
+        };
+
+        assert_eq!(Some(true), positive.decide());
+
+        let negative = Tarski {
+            exists: vec![0],
+            forall: vec![],
+            data: c(sys.get(0), Cmp::Lt),
+            ..positive.clone()
+        };
+
+        assert_eq!(Some(false), negative.decide());
+
+        // exists y. forall x. x^2 > y
+        let below_parabola = Tarski {
+            exists: vec![1],
+            forall: vec![0],
+            data: c(sys.get(1), Cmp::Gt),
+            ..positive.clone()
+        };
+
+        assert_eq!(Some(true), below_parabola.decide());
+
+        // exists y. forall x. x * y > 0 fails at x = 0
+        let product = Tarski {
+            data: c(sys.get(2), Cmp::Gt),
+            ..below_parabola.clone()
+        };
+
+        assert_eq!(Some(false), product.decide());
+
+        let open = Tarski {
+            forall: vec![],
+            ..below_parabola
+        };
+
+        assert_eq!(None, open.decide());
+    }
+
+    #[test]
+    fn two_free_vars() {
+        // a monic quadratic in z has a real root exactly when its discriminant is nonnegative
+        let formula = Tarski::parse("exists z. z^2 + x*z + y = 0").unwrap();
+        let qf = formula.eliminate().unwrap();
+
+        let var = |name: &str| formula.var_dict.iter().position(|v| v == name).unwrap();
+        let holds = |x: i64, y: i64| {
+            let signs = qf
+                .polys()
+                .into_iter()
+                .map(|p| {
+                    let val = p.eval(var("x"), Rat::from(x)).eval(var("y"), Rat::from(y));
+                    let val = val.terms.first().map_or(Rat::from(0), |m| m.val);
+                    (p, val.cmp(&Rat::from(0)))
+                })
+                .collect::<HashMap<_, Ordering>>();
+
+            qf.eval(&signs)
+        };
+
+        for x in -3..=3 {
+            for y in -3..=3 {
+                assert_eq!(x * x >= 4 * y, holds(x, y));
+            }
+        }
+    }
+
+    #[test]
+    fn witness() {
+        let sys = system! { x^2 - 2, x, x^2 + y^2 - 1, y^2 + 1 };
+
+        // x^2 = 2 and x > 0 only at sqrt(2)
+        let root = Tarski {
+            var_dict: sys.var_dict.to_vec(),
+            exists: vec![],
+            forall: vec![],
+            data: T::And(Box::new(c(sys.get(0), Cmp::Eq)), Box::new(c(sys.get(1), Cmp::Gt))),
+        };
+
+        match root.check_sat() {
+            Sat::Sat(model) => {
+                let x = &model["x"];
+                assert_eq!(Ordering::Equal, x.sign_at(&x.p));
+                assert_eq!(Ordering::Greater, x.compare(&Algebraic::from_point(Rat::from(1))));
+                assert_eq!(Ordering::Less, x.compare(&Algebraic::from_point(Rat::from(2))));
+            }
+            Sat::Unsat => panic!("sqrt(2) satisfies the constraints"),
+        }
+
+        // inside the unit disc, the model stays rational
+        let disc = Tarski {
+            exists: vec![0, 1],
+            data: c(sys.get(2), Cmp::Lt),
+            ..root.clone()
+        };
+
+        match disc.check_sat() {
+            Sat::Sat(model) => {
+                assert!(model.values().all(|val| val.is_rational()));
+                assert_eq!(2, model.len());
+            }
+            Sat::Unsat => panic!("the origin is inside the disc"),
+        }
+
+        let negative = Tarski {
+            data: c(sys.get(3), Cmp::Lt),
+            ..root
+        };
+
+        assert!(matches!(negative.check_sat(), Sat::Unsat));
+    }
+}
@@ -0,0 +1,387 @@
+// certified complex root isolation: rectangles are subdivided, and the roots inside each are
+// counted exactly by the argument principle, as cauchy indices along the edges
+use crate::bigrat::{to_rat, BigRat};
+use crate::descartes::unit_variations;
+use crate::field::Field;
+use crate::interval::Interval;
+use crate::rational::{Inexact, Rat};
+use crate::univariate::UPoly;
+
+// the closed rectangle re x im of the complex plane
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rect<T: Field> {
+    pub re: Interval<T>,
+    pub im: Interval<T>,
+}
+
+impl<T: Field> Rect<T> {
+    pub fn new(re: Interval<T>, im: Interval<T>) -> Self {
+        Rect { re, im }
+    }
+
+    pub fn contains(&self, re: &T, im: &T) -> bool {
+        self.re.contains(re) && self.im.contains(im)
+    }
+
+    pub fn overlaps(&self, other: &Rect<T>) -> bool {
+        self.re.overlaps(&other.re) && self.im.overlaps(&other.im)
+    }
+
+    // counterclockwise from the lower left
+    fn corners(&self) -> [(T, T); 4] {
+        [
+            (self.re.lo.clone(), self.im.lo.clone()),
+            (self.re.hi.clone(), self.im.lo.clone()),
+            (self.re.hi.clone(), self.im.hi.clone()),
+            (self.re.lo.clone(), self.im.hi.clone()),
+        ]
+    }
+}
+
+impl<T: Field> UPoly<T> {
+    // one rectangle per distinct complex root, with its multiplicity; each holds no other root,
+    // has none on its boundary, and is no wider or taller than tolerance
+    pub fn complex_root_rects(&self, tolerance: T) -> Vec<(Rect<T>, usize)> {
+        let factors = self.squarefree_decomposition();
+        let mut tolerance = tolerance;
+
+        loop {
+            let mut roots = factors
+                .iter()
+                .flat_map(|(factor, multiplicity)| {
+                    factor
+                        .isolate_complex(tolerance.clone())
+                        .into_iter()
+                        .map(move |rect| (rect, *multiplicity))
+                })
+                .collect::<Vec<_>>();
+
+            // factors are isolated apart, so a rectangle may hold a root of another factor
+            // until they shrink away from each other; multiplicities tell the factors apart
+            let apart = roots.iter().enumerate().all(|(i, (lhs, lhs_mult))| {
+                roots[i + 1..]
+                    .iter()
+                    .all(|(rhs, rhs_mult)| lhs_mult == rhs_mult || !lhs.overlaps(rhs))
+            });
+
+            if apart {
+                roots.sort_by(|(lhs, _), (rhs, _)| {
+                    (&lhs.re.lo, &lhs.im.lo).cmp(&(&rhs.re.lo, &rhs.im.lo))
+                });
+
+                return roots;
+            }
+
+            tolerance = tolerance / T::from(2);
+        }
+    }
+
+    // self is squarefree
+    fn isolate_complex(&self, tolerance: T) -> Vec<Rect<T>> {
+        let n = self.deg();
+
+        if n == 0 {
+            return vec![];
+        }
+
+        // cauchy: every root is below 1 + max |c_i / c_0| in absolute value; a power of two
+        // keeps the corners dyadic
+        let lc = self.0[0].clone();
+        let max = self
+            .0
+            .iter()
+            .map(|c| {
+                let ratio = c.clone() / lc.clone();
+                if ratio < T::zero() {
+                    T::zero() - ratio
+                } else {
+                    ratio
+                }
+            })
+            .max()
+            .unwrap();
+
+        let mut bound = T::one();
+        while bound <= max.clone() + T::one() {
+            bound = bound * T::from(2);
+        }
+
+        let square = |bound: &T| {
+            let side = Interval::new(T::zero() - bound.clone(), bound.clone());
+            Rect::new(side.clone(), side)
+        };
+
+        while square(&bound)
+            .corners()
+            .iter()
+            .any(|(re, im)| self.eval_complex(re, im).0.is_zero())
+        {
+            bound = bound * T::from(2);
+        }
+
+        let rect = square(&bound);
+
+        let mut pending = vec![(rect, n)];
+        let mut isolated = vec![];
+
+        while let Some((rect, count)) = pending.pop() {
+            let small = rect.re.hi.clone() - rect.re.lo.clone() <= tolerance
+                && rect.im.hi.clone() - rect.im.lo.clone() <= tolerance;
+
+            if count == 1 && small {
+                isolated.push(rect);
+                continue;
+            }
+
+            let (re, im) = self.split_point(&rect);
+
+            let quarters = [
+                (
+                    Interval::new(rect.re.lo.clone(), re.clone()),
+                    Interval::new(rect.im.lo.clone(), im.clone()),
+                ),
+                (
+                    Interval::new(re.clone(), rect.re.hi.clone()),
+                    Interval::new(rect.im.lo.clone(), im.clone()),
+                ),
+                (
+                    Interval::new(rect.re.lo.clone(), re.clone()),
+                    Interval::new(im.clone(), rect.im.hi.clone()),
+                ),
+                (
+                    Interval::new(re.clone(), rect.re.hi.clone()),
+                    Interval::new(im.clone(), rect.im.hi.clone()),
+                ),
+            ];
+
+            // the last quarter holds whatever the others do not
+            let mut rest = count;
+
+            for (i, (re, im)) in quarters.into_iter().enumerate() {
+                let quarter = Rect::new(re, im);
+                let count = if i == 3 {
+                    rest
+                } else {
+                    self.count_complex_roots_in(&quarter)
+                };
+
+                rest -= count;
+
+                if count > 0 {
+                    pending.push((quarter, count));
+                }
+            }
+        }
+
+        isolated
+    }
+
+    // the winding number of self around rect's boundary, which must have no root on it
+    // and no corner where self is imaginary
+    fn count_complex_roots_in(&self, rect: &Rect<T>) -> usize {
+        let corners = rect.corners();
+
+        let index = (0..4)
+            .map(|i| {
+                let ((re, im), (next_re, next_im)) = (&corners[i], &corners[(i + 1) % 4]);
+                let (real, imag) = self.along(
+                    (re.clone(), im.clone()),
+                    (next_re.clone() - re.clone(), next_im.clone() - im.clone()),
+                );
+
+                // without a pole on the edge there is nothing to count; descartes can rule one out
+                let ascending = real.0.iter().rev().cloned().collect::<Vec<_>>();
+                if unit_variations(&ascending) == 0 {
+                    0
+                } else {
+                    real.cauchy_index(&imag, &T::zero(), &T::one())
+                }
+            })
+            .sum::<i64>();
+
+        (-index / 2) as usize
+    }
+
+    // a point splitting rect into quarters whose boundaries keep the preconditions of
+    // count_complex_roots_in; only finitely many candidates can fail
+    fn split_point(&self, rect: &Rect<T>) -> (T, T) {
+        // dyadic offsets from the middle, more of them than there are bad ones
+        let mut scale = 1;
+        while scale < 4 * (self.deg() as i64 + 1) {
+            scale *= 2;
+        }
+
+        let candidates = |iv: &Interval<T>| {
+            let (lo, width) = (iv.lo.clone(), iv.hi.clone() - iv.lo.clone());
+
+            (0..scale).flat_map(|j| [j, -j]).map(move |j| {
+                lo.clone()
+                    + width.clone() * (T::one() / T::from(2) + T::from(j) / T::from(4 * scale))
+            })
+        };
+
+        let has_root_on = |start: (T, T), dir: (T, T)| {
+            let (real, imag) = self.along(start, dir);
+            real.gcd(&imag).count_real_roots() > 0
+        };
+        let imaginary_at = |re: &T, im: &T| self.eval_complex(re, im).0.is_zero();
+
+        let re = candidates(&rect.re)
+            .find(|re| {
+                !has_root_on((re.clone(), T::zero()), (T::zero(), T::one()))
+                    && !imaginary_at(re, &rect.im.lo)
+                    && !imaginary_at(re, &rect.im.hi)
+            })
+            .unwrap();
+
+        let im = candidates(&rect.im)
+            .find(|im| {
+                !has_root_on((T::zero(), im.clone()), (T::one(), T::zero()))
+                    && [&rect.re.lo, &re, &rect.re.hi]
+                        .iter()
+                        .all(|x| !imaginary_at(x, im))
+            })
+            .unwrap();
+
+        (re, im)
+    }
+
+    // real and imaginary parts of self(start + t dir), as polynomials in t
+    fn along(&self, start: (T, T), dir: (T, T)) -> (UPoly<T>, UPoly<T>) {
+        let n = self.0.len();
+        let (mut re, mut im) = (vec![T::zero(); n], vec![T::zero(); n]);
+
+        // horner on ascending coefficients: acc = acc (start + t dir) + c
+        for (k, c) in self.0.iter().enumerate() {
+            for j in (0..=k).rev() {
+                let (mut next_re, mut next_im) = (T::zero(), T::zero());
+
+                if j < k {
+                    next_re = re[j].clone() * start.0.clone() - im[j].clone() * start.1.clone();
+                    next_im = re[j].clone() * start.1.clone() + im[j].clone() * start.0.clone();
+                }
+
+                if j > 0 {
+                    next_re = next_re + re[j - 1].clone() * dir.0.clone()
+                        - im[j - 1].clone() * dir.1.clone();
+                    next_im = next_im
+                        + re[j - 1].clone() * dir.1.clone()
+                        + im[j - 1].clone() * dir.0.clone();
+                }
+
+                re[j] = next_re;
+                im[j] = next_im;
+            }
+
+            re[0] = re[0].clone() + c.clone();
+        }
+
+        re.reverse();
+        im.reverse();
+
+        (UPoly(re).trim(), UPoly(im).trim())
+    }
+
+    // real and imaginary parts of self(re + im i)
+    fn eval_complex(&self, re: &T, im: &T) -> (T, T) {
+        self.0
+            .iter()
+            .fold((T::zero(), T::zero()), |(acc_re, acc_im), c| {
+                (
+                    acc_re.clone() * re.clone() - acc_im.clone() * im.clone() + c.clone(),
+                    acc_re * im.clone() + acc_im * re.clone(),
+                )
+            })
+    }
+}
+
+impl UPoly<Rat> {
+    // computed exactly, since rounding would void the count; fails only when a corner
+    // does not fit in a Rat
+    pub fn try_complex_root_rects(
+        &self,
+        tolerance: Rat,
+    ) -> Result<Vec<(Rect<Rat>, usize)>, Inexact> {
+        let p = self.map(|c| BigRat::from(*c));
+        let to_interval = |iv: Interval<BigRat>| -> Result<Interval<Rat>, Inexact> {
+            Ok(Interval::new(to_rat(iv.lo)?, to_rat(iv.hi)?))
+        };
+
+        p.complex_root_rects(BigRat::from(tolerance))
+            .into_iter()
+            .map(|(rect, multiplicity)| {
+                Ok((
+                    Rect::new(to_interval(rect.re)?, to_interval(rect.im)?),
+                    multiplicity,
+                ))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::interval::Interval;
+    use crate::rational::Rat;
+    use crate::univariate::upoly;
+
+    #[test]
+    fn isolate() {
+        let tol = Rat::from(1) / Rat::from(8);
+
+        // (z^2 + 1)^2 (z - 1)
+        let roots = upoly(&[1, -1, 2, -2, 1, -1])
+            .try_complex_root_rects(tol)
+            .unwrap();
+        assert_eq!(3, roots.len());
+
+        let at = |re: i64, im: i64| {
+            roots
+                .iter()
+                .find(|(rect, _)| rect.contains(&Rat::from(re), &Rat::from(im)))
+                .map(|(_, multiplicity)| *multiplicity)
+        };
+
+        assert_eq!(Some(1), at(1, 0));
+        assert_eq!(Some(2), at(0, 1));
+        assert_eq!(Some(2), at(0, -1));
+
+        // z^4 + 1, with roots (+-1 +- i) / sqrt(2)
+        let roots = upoly(&[1, 0, 0, 0, 1]).try_complex_root_rects(tol).unwrap();
+        assert_eq!(4, roots.len());
+
+        let half = 0.5f64.sqrt();
+
+        for (rect, multiplicity) in &roots {
+            let near = |iv: &Interval<Rat>| {
+                let (lo, hi) = (f64::from(iv.lo), f64::from(iv.hi));
+                (lo < half && half < hi) || (lo < -half && -half < hi)
+            };
+
+            assert!(near(&rect.re) && near(&rect.im));
+            assert_eq!(1, *multiplicity);
+        }
+
+        // (z + 1)(z^2 + z + 1), all in the left half plane
+        let roots = upoly(&[1, 2, 2, 1]).try_complex_root_rects(tol).unwrap();
+        assert_eq!(3, roots.len());
+        assert!(roots.iter().all(|(rect, _)| rect.re.hi < Rat::from(0)));
+
+        assert!(upoly(&[4]).try_complex_root_rects(tol).unwrap().is_empty());
+
+        // (z - 1)^2 (100z - 101), whose two roots fit in one rectangle of the tolerance
+        let roots = upoly(&[100, -301, 302, -101])
+            .try_complex_root_rects(tol)
+            .unwrap();
+        assert_eq!(2, roots.len());
+
+        let (one, near_one) = (Rat::from(1), Rat::from(101) / Rat::from(100));
+        let zero = Rat::from(0);
+
+        for (rect, multiplicity) in &roots {
+            let expected = if rect.contains(&one, &zero) { 2 } else { 1 };
+            assert_eq!(expected, *multiplicity);
+            assert!(rect.contains(&one, &zero) != rect.contains(&near_one, &zero));
+        }
+    }
+}
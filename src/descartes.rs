@@ -0,0 +1,195 @@
+// real root isolation by descartes' rule of signs, bisecting (0, 1) after a change of scale
+use std::ops;
+
+use crate::bigint::BigInt;
+use crate::bigrat::{int_coefs, to_rat, BigRat, IntPoly};
+use crate::field::Zero;
+use crate::rational::{Inexact, Rat};
+use crate::univariate::{sign_variations, Root, UPoly};
+
+impl UPoly<Rat> {
+    // vincent-collins-akritas: ascending, disjoint isolating intervals, one per distinct real
+    // root, with no root at an endpoint; fails only when an endpoint does not fit in a Rat
+    pub fn isolate_real_roots(&self) -> Result<Vec<Root<Rat>>, Inexact> {
+        let p = self.map(|c| BigRat::from(*c)).trim();
+
+        if p.deg() == 0 {
+            return Ok(vec![]);
+        }
+
+        let p = p.squarefree_part();
+        let f = int_coefs(&p);
+
+        let mut roots = vec![];
+
+        // zero stays a root of f, so the intervals on either side bisect away from it
+        if f[0].is_zero() {
+            roots.push(Root::Point(BigRat::zero()));
+        }
+
+        // cauchy: every root is below 1 + max |f_i / f_n| < 2^k in absolute value
+        let n = f.len() - 1;
+        let max = f.iter().map(|c| c.abs()).max().unwrap();
+        let k = (max.bits() + 2).saturating_sub(f[n].bits()) as u32;
+        let bound = BigRat::new(BigInt::from(2i64).pow(k), BigInt::one());
+
+        for sign in [1, -1] {
+            // f(sign 2^k x), whose roots in (0, 1) are those of f on one side of zero, scaled
+            let mut power = BigInt::one();
+            let g = f
+                .iter()
+                .map(|c| {
+                    let c = c.clone() * power.clone();
+                    power = power.clone() * BigInt::from(2i64).pow(k) * BigInt::from(sign);
+                    c
+                })
+                .collect::<IntPoly>();
+
+            let mut found = vec![];
+            isolate_unit(g, BigRat::zero(), BigRat::from(1), &mut found);
+
+            let bound = bound.clone() * sign;
+
+            roots.extend(found.into_iter().map(|root| match root {
+                Root::Point(x) => Root::Point(x * bound.clone()),
+                Root::Interval(start, end) if sign > 0 => {
+                    Root::Interval(start * bound.clone(), end * bound.clone())
+                }
+                Root::Interval(start, end) => {
+                    Root::Interval(end * bound.clone(), start * bound.clone())
+                }
+            }));
+        }
+
+        roots.sort_by(|lhs, rhs| lhs.bounds().0.cmp(&rhs.bounds().0));
+
+        roots
+            .into_iter()
+            .map(|root| match root {
+                Root::Point(x) => Ok(Root::Point(to_rat(x)?)),
+                Root::Interval(start, end) => Ok(Root::Interval(to_rat(start)?, to_rat(end)?)),
+            })
+            .collect()
+    }
+}
+
+// the roots of g in (0, 1), mapped onto (start, start + width), in ascending order
+fn isolate_unit(g: IntPoly, start: BigRat, width: BigRat, found: &mut Vec<Root<BigRat>>) {
+    let variations = unit_variations(&g);
+    let at_end = g.iter().fold(BigInt::zero(), |acc, c| acc + c.clone());
+
+    if variations == 0 {
+        return;
+    }
+
+    // bisect further when a root of a neighbouring interval sits on an endpoint
+    if variations == 1 && !g[0].is_zero() && !at_end.is_zero() {
+        found.push(Root::Interval(start.clone(), start + width));
+        return;
+    }
+
+    // 2^n g(x / 2) covers the left half, and its shift by one the right
+    let n = g.len() - 1;
+    let left = g
+        .iter()
+        .enumerate()
+        .map(|(i, c)| c.clone() * BigInt::from(2i64).pow((n - i) as u32))
+        .collect::<IntPoly>();
+    let right = taylor_shift(left.clone());
+
+    let half = width / BigRat::from(2);
+    let mid = start.clone() + half.clone();
+
+    isolate_unit(left, start, half.clone(), found);
+
+    if right[0].is_zero() {
+        found.push(Root::Point(mid.clone()));
+    }
+
+    isolate_unit(right, mid, half, found);
+}
+
+// descartes on (0, 1): (x + 1)^n a(1 / (x + 1)) has as many positive roots as a has in
+// (0, 1), up to an even number, so its sign variations bound them
+pub(crate) fn unit_variations<T: Clone + Ord + From<i64> + ops::Add<Output = T>>(a: &[T]) -> usize {
+    let mut reversed = a.to_vec();
+    reversed.reverse();
+
+    let zero = T::from(0);
+    sign_variations(taylor_shift(reversed).iter().map(|c| c.cmp(&zero)))
+}
+
+// a(x + 1)
+fn taylor_shift<T: Clone + ops::Add<Output = T>>(mut a: Vec<T>) -> Vec<T> {
+    let n = a.len().saturating_sub(1);
+
+    for i in 0..n {
+        for j in (i..n).rev() {
+            a[j] = a[j].clone() + a[j + 1].clone();
+        }
+    }
+
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rational::Rat;
+    use crate::univariate::{upoly, Root, UPoly};
+    use std::cmp::Ordering;
+
+    // each root isolated exactly once, ascending and without overlaps
+    fn check(p: &UPoly<Rat>, expected: usize) {
+        let roots = p.isolate_real_roots().unwrap();
+        let sign = |x: &Rat| p.eval(x).cmp(&Rat::from(0));
+
+        assert_eq!(expected, roots.len());
+        assert_eq!(p.count_real_roots(), roots.len());
+
+        for root in &roots {
+            match root {
+                Root::Point(x) => assert_eq!(Ordering::Equal, sign(x)),
+                Root::Interval(start, end) => {
+                    assert!(start < end);
+                    assert_eq!(1, p.count_roots_in(start, end));
+                    assert_ne!(Ordering::Equal, sign(start));
+                    assert_ne!(Ordering::Equal, sign(end));
+                }
+            }
+        }
+
+        for pair in roots.windows(2) {
+            assert!(pair[0].bounds().1 <= pair[1].bounds().0);
+        }
+    }
+
+    #[test]
+    fn isolate() {
+        // (x^2 - 2)(x - 1)(x + 3)
+        check(&upoly(&[1, 2, -5, -4, 6]), 4);
+
+        // x^3 - x, with a root at zero and two on dyadic midpoints
+        check(&upoly(&[1, 0, -1, 0]), 3);
+        assert_eq!(
+            vec![
+                Root::Point(Rat::from(-1)),
+                Root::Point(Rat::from(0)),
+                Root::Point(Rat::from(1))
+            ],
+            upoly(&[1, 0, -1, 0]).isolate_real_roots().unwrap()
+        );
+
+        // (x - 1)^3 (x^2 + 1)
+        check(&upoly(&[1, -3, 4, -4, 3, -1]), 1);
+
+        check(&upoly(&[1, 0, 1]), 0);
+        check(&upoly(&[3]), 0);
+
+        // (x - 1)(x - 2)...(x - 12)
+        let wilkinson = (1..=12).fold(upoly(&[1]), |acc, i| acc * upoly(&[1, -i]));
+        check(&wilkinson, 12);
+
+        // (x^2 - 2)(x^2 - 2.0001), two pairs of roots 1/30000 apart
+        check(&upoly(&[10000, 0, -40001, 0, 40002]), 4);
+    }
+}
@@ -0,0 +1,996 @@
+// factorization over Q: zassenhaus for one variable, evaluation and hensel lifting for several
+use crate::bigint::BigInt;
+use crate::bigrat::{int_coefs, to_rat, BigRat, IntPoly};
+use crate::field::{Fraction, One, Zero};
+use crate::poly::mono::Mono;
+use crate::poly::Poly;
+use crate::rational::{Inexact, Rat};
+use crate::univariate::UPoly;
+
+// dense, ascending coefficients in 0..p for a small odd prime p
+type ModPoly = Vec<i64>;
+
+// the exponents of a monomial, by variable
+type Vars = Vec<(usize, u64)>;
+
+// a constant and the irreducible factors with their multiplicities
+pub type Factorization<P> = (Rat, Vec<(P, usize)>);
+
+impl UPoly<Rat> {
+    // irreducible factors over Q with multiplicities, each with coprime integer coefficients
+    // and a positive leading coefficient; the constant makes the product equal self. fails when
+    // a coefficient of the result does not fit in a Rat
+    pub fn factor(&self) -> Result<Factorization<UPoly<Rat>>, Inexact> {
+        let p = self.map(|c| BigRat::from(*c)).trim();
+
+        if p.is_zero() {
+            return Ok((Rat::from(0), vec![]));
+        }
+
+        let mut factors = vec![];
+
+        for (part, multiplicity) in p.squarefree_decomposition() {
+            for factor in factor_squarefree(&part) {
+                factors.push((factor, multiplicity));
+            }
+        }
+
+        let lc = factors
+            .iter()
+            .fold(p.0[0].clone(), |acc, (factor, multiplicity)| {
+                (0..*multiplicity).fold(acc, |acc, _| acc / factor.0[0].clone())
+            });
+
+        let factors = factors
+            .into_iter()
+            .map(|(factor, multiplicity)| {
+                let coefs = factor.0.into_iter().map(to_rat).collect::<Result<_, _>>()?;
+                Ok((UPoly(coefs), multiplicity))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok((to_rat(lc)?, factors))
+    }
+}
+
+impl Poly<Rat> {
+    // irreducible factors over Q with multiplicities, each normalized as by norm(); the
+    // constant makes the product equal self. fails when a coefficient of the result does not fit
+    // in a Rat
+    pub fn factor(&self) -> Result<Factorization<Poly<Rat>>, Inexact> {
+        if self.is_zero() {
+            return Ok((Rat::from(0), vec![]));
+        }
+
+        let p = self.map(|c| BigRat::from(*c));
+        let factors = factor_poly(&p);
+
+        let lc = factors
+            .iter()
+            .fold(p.lt_mono().val, |acc, (factor, multiplicity)| {
+                (0..*multiplicity).fold(acc, |acc, _| acc / factor.lt_mono().val)
+            });
+
+        let factors = factors
+            .into_iter()
+            .map(|(factor, multiplicity)| {
+                let terms = factor
+                    .terms
+                    .into_iter()
+                    .map(|term| {
+                        Ok(Mono {
+                            val: to_rat(term.val)?,
+                            vars: term.vars,
+                        })
+                    })
+                    .collect::<Result<_, _>>()?;
+                Ok((Poly { terms }, multiplicity))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok((to_rat(lc)?, factors))
+    }
+}
+
+fn factor_poly(p: &Poly<BigRat>) -> Vec<(Poly<BigRat>, usize)> {
+    // the main variable, where making the polynomial monic raises degrees the least
+    let cost = |x: usize| {
+        let lc = p.coefs(x).swap_remove(0);
+        let lc_deg = lc
+            .terms
+            .iter()
+            .map(|term| {
+                term.vars
+                    .iter()
+                    .map(|(_, pow)| *pow as usize)
+                    .sum::<usize>()
+            })
+            .max();
+
+        ((p.deg(x) - 1) * lc_deg.unwrap_or(0), p.deg(x), x)
+    };
+
+    let Some(x) = p
+        .terms
+        .iter()
+        .flat_map(|term| term.vars.iter().map(|(var, _)| *var))
+        .min_by_key(|x| cost(*x))
+    else {
+        return vec![];
+    };
+
+    // the content lacks x, so this recursion ends
+    let (content, parts) = p.squarefree_decomposition(x);
+    let mut factors = factor_poly(&content);
+
+    for (part, multiplicity) in parts {
+        for factor in factor_squarefree_in(&part, x) {
+            factors.push((factor, multiplicity));
+        }
+    }
+
+    factors
+}
+
+// part is squarefree and primitive in x
+fn factor_squarefree_in(part: &Poly<BigRat>, x: usize) -> Vec<Poly<BigRat>> {
+    if part
+        .terms
+        .iter()
+        .all(|term| term.vars.iter().all(|(var, _)| *var == x))
+    {
+        return factor_squarefree(&UPoly::from_poly(part, x))
+            .into_iter()
+            .map(|factor| factor.to_poly(x))
+            .collect();
+    }
+
+    // monic in x through g(x) = l^(n - 1) part(x / l), l the leading coefficient
+    let coefs = part.coefs(x);
+    let l = coefs[0].clone();
+
+    let mut scale = Poly::constant(BigRat::one());
+    let mut monic_coefs = vec![Poly::constant(BigRat::one())];
+
+    for coef in &coefs[1..] {
+        monic_coefs.push(coef.mul_ref(&scale));
+        scale = scale.mul_ref(&l);
+    }
+
+    let g = Poly::from_uni_fmt(monic_coefs, x);
+
+    factor_monic_in(&g, x)
+        .iter()
+        .map(|factor| compose(factor, x, &(l.clone() * Poly::var(x, 1))).primitive_part(x))
+        .collect()
+}
+
+// g is squarefree, monic in x, and involves some other variable
+fn factor_monic_in(g: &Poly<BigRat>, x: usize) -> Vec<Poly<BigRat>> {
+    let mut ys = g
+        .terms
+        .iter()
+        .flat_map(|term| term.vars.iter().map(|(var, _)| *var))
+        .filter(|var| *var != x)
+        .collect::<Vec<_>>();
+    ys.sort();
+    ys.dedup();
+
+    // among a few evaluation points keeping the image squarefree, the one with fewest factors
+    let mut best: Option<(Vec<i64>, Vec<UPoly<BigRat>>)> = None;
+    let mut seed = 1i64;
+    let mut tried = 0;
+
+    for attempt in 0i64.. {
+        // only finitely many points leave the image non-squarefree; widen the range as they fail
+        let range = 5 + attempt / 4;
+        let point = ys
+            .iter()
+            .map(|_| {
+                seed = (seed * 1103515245 + 12345) % 2147483648;
+                if attempt == 0 {
+                    0
+                } else {
+                    seed % (2 * range + 1) - range
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let image = ys
+            .iter()
+            .zip(&point)
+            .fold(g.clone(), |acc, (y, a)| acc.eval(*y, BigRat::from(*a)));
+        let u = UPoly::from_poly(&image, x);
+
+        if u.gcd(&u.derivative()).deg() > 0 {
+            continue;
+        }
+
+        let factors = factor_squarefree(&u)
+            .into_iter()
+            .map(|factor| factor.monic())
+            .collect::<Vec<_>>();
+
+        if best
+            .as_ref()
+            .is_none_or(|(_, best)| factors.len() < best.len())
+        {
+            best = Some((point, factors));
+        }
+
+        tried += 1;
+
+        if tried == 3 || best.as_ref().is_some_and(|(_, best)| best.len() == 1) {
+            break;
+        }
+    }
+
+    let (point, images) = best.unwrap();
+
+    if images.len() == 1 {
+        return vec![g.clone()];
+    }
+
+    // every factor of g is a product of lifted factors up to this degree in the shifted variables
+    let z_deg = |term: &Mono<BigRat>| {
+        term.vars
+            .iter()
+            .filter(|(var, _)| *var != x)
+            .map(|(_, pow)| *pow as usize)
+            .sum::<usize>()
+    };
+    let d = g.terms.iter().map(z_deg).max().unwrap();
+
+    let shift = |p: &Poly<BigRat>, sign: i64| {
+        ys.iter().zip(&point).fold(p.clone(), |acc, (y, a)| {
+            compose(
+                &acc,
+                *y,
+                &(Poly::var(*y, 1) + Poly::constant(BigRat::from(a * sign))),
+            )
+        })
+    };
+    let truncate = |p: Poly<BigRat>| Poly {
+        terms: p
+            .terms
+            .into_iter()
+            .filter(|term| z_deg(term) <= d)
+            .collect(),
+    };
+
+    let lifted = lift_in(&shift(g, 1), x, &images, d);
+
+    recombine(lifted, g.clone(), |chosen, rest| {
+        let product = chosen
+            .iter()
+            .fold(Poly::constant(BigRat::one()), |acc, factor| {
+                truncate(acc.mul_ref(factor))
+            });
+        let candidate = shift(&product, -1);
+
+        rest.try_divide(&candidate).map(|quot| (candidate, quot))
+    })
+}
+
+// p with var replaced by sub
+fn compose(p: &Poly<BigRat>, var: usize, sub: &Poly<BigRat>) -> Poly<BigRat> {
+    p.coefs(var)
+        .into_iter()
+        .fold(Poly::constant(BigRat::zero()), |acc, coef| {
+            acc.mul_ref(sub) + coef
+        })
+}
+
+// lifts the monic images, pairwise coprime and multiplying to f at the origin, to monic
+// factors of f up to degree d in the variables other than x
+fn lift_in(f: &Poly<BigRat>, x: usize, images: &[UPoly<BigRat>], d: usize) -> Vec<Poly<BigRat>> {
+    // homogeneous parts by degree in the variables other than x
+    let mut target = vec![Poly::constant(BigRat::zero()); d + 1];
+
+    for term in &f.terms {
+        let deg = term
+            .vars
+            .iter()
+            .filter(|(var, _)| *var != x)
+            .map(|(_, pow)| *pow as usize)
+            .sum::<usize>();
+
+        if deg <= d {
+            target[deg].terms.push(term.clone());
+        }
+    }
+
+    let sum = |parts: Vec<Poly<BigRat>>| {
+        parts
+            .into_iter()
+            .fold(Poly::constant(BigRat::zero()), |acc, part| acc + part)
+    };
+
+    let mut lifted = vec![];
+
+    for (i, g0) in images[..images.len() - 1].iter().enumerate() {
+        let h0 = images[i + 1..]
+            .iter()
+            .fold(UPoly(vec![BigRat::one()]), |acc, image| acc * image.clone());
+
+        // s g0 + t h0 = 1
+        let s = g0.inverse_mod(&h0).unwrap();
+        let t = (UPoly(vec![BigRat::one()]) - s.clone() * g0.clone())
+            .div_rem(&h0)
+            .0;
+
+        let (mut g, mut h) = (vec![g0.to_poly(x)], vec![h0.to_poly(x)]);
+
+        for k in 1..=d {
+            // what g_k h_0 + g_0 h_k must make up in degree k
+            let error = (1..k).fold(target[k].clone(), |acc, j| acc - g[j].mul_ref(&h[k - j]));
+            let (mut g_k, mut h_k) = (
+                Poly::constant(BigRat::zero()),
+                Poly::constant(BigRat::zero()),
+            );
+
+            // each coefficient c of the error needs tau h0 + sigma g0 = c
+            for (vars, c) in by_monomial(&error, x) {
+                let tau = (t.clone() * c.clone()).div_rem(g0).1;
+                let sigma = (s.clone() * c).div_rem(&h0).1;
+                let mono = Poly {
+                    terms: vec![Mono {
+                        val: BigRat::one(),
+                        vars,
+                    }],
+                };
+
+                g_k = g_k + tau.to_poly(x) * mono.clone();
+                h_k = h_k + sigma.to_poly(x) * mono;
+            }
+
+            g.push(g_k);
+            h.push(h_k);
+        }
+
+        lifted.push(sum(g));
+        target = h;
+    }
+
+    lifted.push(sum(target));
+
+    lifted
+}
+
+// p grouped by its monomials in the variables other than x, with coefficients in x
+fn by_monomial(p: &Poly<BigRat>, x: usize) -> Vec<(Vars, UPoly<BigRat>)> {
+    let mut parts: Vec<(Vars, Poly<BigRat>)> = vec![];
+
+    for term in &p.terms {
+        let (vars, x_vars) = term
+            .vars
+            .iter()
+            .partition::<Vec<_>, _>(|(var, _)| *var != x);
+        let x_part = Poly {
+            terms: vec![Mono {
+                val: term.val.clone(),
+                vars: x_vars,
+            }],
+        };
+
+        match parts.iter_mut().find(|(key, _)| *key == vars) {
+            Some((_, coef)) => *coef = coef.clone() + x_part,
+            None => parts.push((vars, x_part)),
+        }
+    }
+
+    parts
+        .into_iter()
+        .map(|(vars, coef)| (vars, UPoly::from_poly(&coef, x)))
+        .collect()
+}
+
+// the factors of rest, given the lifted factors it is a product of: the smallest subsets are
+// tried first, and divide turns one into a candidate factor and the quotient by it, if any
+fn recombine<P>(
+    mut lifted: Vec<P>,
+    mut rest: P,
+    divide: impl Fn(&[&P], &P) -> Option<(P, P)>,
+) -> Vec<P> {
+    let mut factors = vec![];
+    let mut size = 1;
+
+    while 2 * size <= lifted.len() {
+        let found = subsets(lifted.len(), size).into_iter().find_map(|subset| {
+            let chosen = subset.iter().map(|i| &lifted[*i]).collect::<Vec<_>>();
+            divide(&chosen, &rest).map(|(candidate, quot)| (subset, candidate, quot))
+        });
+
+        match found {
+            Some((subset, candidate, quot)) => {
+                factors.push(candidate);
+                rest = quot;
+
+                let mut i = 0;
+                lifted.retain(|_| {
+                    i += 1;
+                    !subset.contains(&(i - 1))
+                });
+            }
+            None => size += 1,
+        }
+    }
+
+    factors.push(rest);
+
+    factors
+}
+
+// k element subsets of 0..n in lexicographic order
+fn subsets(n: usize, k: usize) -> Vec<Vec<usize>> {
+    if k == 0 {
+        return vec![vec![]];
+    }
+
+    (k - 1..n)
+        .flat_map(|last| {
+            subsets(last, k - 1).into_iter().map(move |mut subset| {
+                subset.push(last);
+                subset
+            })
+        })
+        .collect()
+}
+
+// irreducible factors of a squarefree polynomial, each with coprime integer coefficients
+// and a positive leading coefficient
+fn factor_squarefree(p: &UPoly<BigRat>) -> Vec<UPoly<BigRat>> {
+    let p = p.clone().trim();
+
+    if p.deg() == 0 {
+        return vec![];
+    }
+
+    let f = int_coefs(&p);
+
+    // monic through g(x) = l^(n - 1) f(x / l), l the leading coefficient
+    let n = f.len() - 1;
+    let l = f[n].clone();
+    let mut g = vec![BigInt::one()];
+    let mut power = BigInt::one();
+
+    for c in f[..n].iter().rev() {
+        g.push(c.clone() * power.clone());
+        power = power * l.clone();
+    }
+    g.reverse();
+
+    factor_monic(&g)
+        .into_iter()
+        .map(|factor| {
+            // factor(l x), made primitive
+            let mut power = BigInt::one();
+            let mut coefs = factor
+                .into_iter()
+                .map(|c| {
+                    let c = BigRat::new(c * power.clone(), BigInt::one());
+                    power = power.clone() * l.clone();
+                    c
+                })
+                .collect::<Vec<_>>();
+            coefs.reverse();
+
+            let mut scale = BigRat::primitive(&coefs);
+            if coefs[0] < BigRat::zero() {
+                scale = scale * -1;
+            }
+
+            UPoly(coefs.into_iter().map(|c| c / scale.clone()).collect())
+        })
+        .collect()
+}
+
+// zassenhaus: factor modulo a prime, lift the factors hensel-wise past the coefficient
+// bound, and recombine subsets that divide f over the integers
+fn factor_monic(f: &IntPoly) -> Vec<IntPoly> {
+    let n = f.len() - 1;
+
+    if n == 1 {
+        return vec![f.clone()];
+    }
+
+    let mut best: Option<(i64, Vec<ModPoly>)> = None;
+    let mut tried = 0;
+
+    for p in (3..).step_by(2).filter(|p| {
+        (3..)
+            .step_by(2)
+            .take_while(|d| d * d <= *p)
+            .all(|d| p % d != 0)
+    }) {
+        let image = reduce(f, p);
+
+        if gcd_mod(&image, &derivative_mod(&image, p), p).len() > 1 {
+            continue;
+        }
+
+        let factors = factor_mod(&image, p);
+
+        if best
+            .as_ref()
+            .is_none_or(|(_, best)| factors.len() < best.len())
+        {
+            best = Some((p, factors));
+        }
+
+        tried += 1;
+
+        if tried == 3 {
+            break;
+        }
+    }
+
+    let (p, modular) = best.unwrap();
+
+    if modular.len() == 1 {
+        return vec![f.clone()];
+    }
+
+    // mignotte: a monic factor's coefficients are below 2^n sqrt(n + 1) max |f_i|
+    let max = f.iter().map(|c| c.abs()).max().unwrap();
+    let bound =
+        BigInt::from(2i64).pow(n as u32) * BigInt::from(n as i64 + 1) * max * BigInt::from(2i64);
+
+    let mut k = 1;
+    let mut modulus = BigInt::from(p);
+
+    while modulus <= bound {
+        modulus = modulus * BigInt::from(p);
+        k += 1;
+    }
+
+    recombine(lift(f, &modular, p, k), f.clone(), |chosen, rest| {
+        let product = chosen.iter().fold(vec![BigInt::one()], |acc, factor| {
+            reduce_big(&mul_int(&acc, factor), &modulus)
+        });
+        let candidate = product
+            .iter()
+            .map(|c| symmetric(c, &modulus))
+            .collect::<IntPoly>();
+
+        divide_monic(rest, &candidate).map(|quot| (candidate, quot))
+    })
+}
+
+// the monic modular factors lifted to monic factors of f modulo p^k
+fn lift(f: &IntPoly, factors: &[ModPoly], p: i64, k: usize) -> Vec<IntPoly> {
+    let mut target = f.clone();
+    let mut lifted = vec![];
+
+    for (i, g0) in factors[..factors.len() - 1].iter().enumerate() {
+        let h0 = factors[i + 1..]
+            .iter()
+            .fold(vec![1], |acc, factor| mul_mod(&acc, factor, p));
+        let (s, t) = ext_gcd_mod(g0, &h0, p);
+
+        let (mut g, mut h) = (to_int(g0), to_int(&h0));
+        let mut modulus = BigInt::from(p);
+
+        for _ in 1..k {
+            let next = modulus.clone() * BigInt::from(p);
+
+            // target - g h vanishes modulo the current modulus
+            let error = sub_int(&target, &mul_int(&g, &h))
+                .iter()
+                .map(|c| to_small(&c.div_rem(&modulus).0, p))
+                .collect::<Vec<_>>();
+            let error = trim_mod(error);
+
+            let tau = div_rem_mod(&mul_mod(&t, &error, p), g0, p).1;
+            let sigma = div_rem_mod(&mul_mod(&s, &error, p), &h0, p).1;
+
+            g = reduce_big(&add_int(&g, &scale_int(&to_int(&tau), &modulus)), &next);
+            h = reduce_big(&add_int(&h, &scale_int(&to_int(&sigma), &modulus)), &next);
+            modulus = next;
+        }
+
+        lifted.push(g);
+        target = h;
+    }
+
+    lifted.push(target);
+
+    lifted
+}
+
+fn to_small(c: &BigInt, p: i64) -> i64 {
+    i64::try_from(&modulo(c, &BigInt::from(p))).unwrap()
+}
+
+fn to_int(a: &ModPoly) -> IntPoly {
+    a.iter().map(|c| BigInt::from(*c)).collect()
+}
+
+fn reduce(a: &IntPoly, p: i64) -> ModPoly {
+    trim_mod(a.iter().map(|c| to_small(c, p)).collect())
+}
+
+fn modulo(a: &BigInt, m: &BigInt) -> BigInt {
+    let rem = a.div_rem(m).1;
+
+    if rem.is_negative() {
+        rem + m.clone()
+    } else {
+        rem
+    }
+}
+
+// representative in (-m / 2, m / 2]
+fn symmetric(a: &BigInt, m: &BigInt) -> BigInt {
+    let rem = modulo(a, m);
+
+    if rem.clone() * BigInt::from(2i64) > *m {
+        rem - m.clone()
+    } else {
+        rem
+    }
+}
+
+fn reduce_big(a: &IntPoly, m: &BigInt) -> IntPoly {
+    a.iter().map(|c| modulo(c, m)).collect()
+}
+
+fn add_int(a: &IntPoly, b: &IntPoly) -> IntPoly {
+    (0..a.len().max(b.len()))
+        .map(|i| {
+            a.get(i).cloned().unwrap_or_else(BigInt::zero)
+                + b.get(i).cloned().unwrap_or_else(BigInt::zero)
+        })
+        .collect()
+}
+
+fn sub_int(a: &IntPoly, b: &IntPoly) -> IntPoly {
+    add_int(a, &b.iter().map(|c| -c.clone()).collect())
+}
+
+fn scale_int(a: &IntPoly, c: &BigInt) -> IntPoly {
+    a.iter().map(|v| v.clone() * c.clone()).collect()
+}
+
+fn mul_int(a: &IntPoly, b: &IntPoly) -> IntPoly {
+    let mut res = vec![BigInt::zero(); a.len() + b.len() - 1];
+
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            res[i + j] = res[i + j].clone() + x.clone() * y.clone();
+        }
+    }
+
+    res
+}
+
+// the quotient by a monic divisor, when it leaves no remainder
+fn divide_monic(a: &IntPoly, b: &IntPoly) -> Option<IntPoly> {
+    let mut rem = a.clone();
+    let mut quot = vec![BigInt::zero(); a.len() + 1 - b.len()];
+
+    for i in (0..quot.len()).rev() {
+        let c = rem[i + b.len() - 1].clone();
+
+        for (j, v) in b.iter().enumerate() {
+            rem[i + j] = rem[i + j].clone() - c.clone() * v.clone();
+        }
+
+        quot[i] = c;
+    }
+
+    if rem.iter().all(|c| c.is_zero()) {
+        Some(quot)
+    } else {
+        None
+    }
+}
+
+fn trim_mod(mut a: ModPoly) -> ModPoly {
+    while a.last() == Some(&0) {
+        a.pop();
+    }
+    a
+}
+
+fn pow_mod(mut base: i64, mut exp: i64, p: i64) -> i64 {
+    let mut res = 1;
+
+    while exp > 0 {
+        if exp % 2 == 1 {
+            res = res * base % p;
+        }
+        base = base * base % p;
+        exp /= 2;
+    }
+
+    res
+}
+
+fn sub_mod(a: &ModPoly, b: &ModPoly, p: i64) -> ModPoly {
+    trim_mod(
+        (0..a.len().max(b.len()))
+            .map(|i| (a.get(i).unwrap_or(&0) - b.get(i).unwrap_or(&0)).rem_euclid(p))
+            .collect(),
+    )
+}
+
+fn mul_mod(a: &ModPoly, b: &ModPoly, p: i64) -> ModPoly {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+
+    let mut res = vec![0; a.len() + b.len() - 1];
+
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            res[i + j] = (res[i + j] + x * y) % p;
+        }
+    }
+
+    trim_mod(res)
+}
+
+fn div_rem_mod(a: &ModPoly, b: &ModPoly, p: i64) -> (ModPoly, ModPoly) {
+    let mut rem = trim_mod(a.clone());
+
+    if rem.len() < b.len() {
+        return (vec![], rem);
+    }
+
+    let inv = pow_mod(b[b.len() - 1], p - 2, p);
+    let mut quot = vec![0; rem.len() + 1 - b.len()];
+
+    for i in (0..quot.len()).rev() {
+        let c = rem[i + b.len() - 1] * inv % p;
+
+        for (j, v) in b.iter().enumerate() {
+            rem[i + j] = (rem[i + j] - c * v).rem_euclid(p);
+        }
+
+        quot[i] = c;
+    }
+
+    (trim_mod(quot), trim_mod(rem))
+}
+
+fn monic_mod(a: &ModPoly, p: i64) -> ModPoly {
+    match a.last() {
+        Some(lc) => {
+            let inv = pow_mod(*lc, p - 2, p);
+            a.iter().map(|c| c * inv % p).collect()
+        }
+        None => vec![],
+    }
+}
+
+fn gcd_mod(a: &ModPoly, b: &ModPoly, p: i64) -> ModPoly {
+    let (mut a, mut b) = (trim_mod(a.clone()), trim_mod(b.clone()));
+
+    while !b.is_empty() {
+        let rem = div_rem_mod(&a, &b, p).1;
+        a = b;
+        b = rem;
+    }
+
+    monic_mod(&a, p)
+}
+
+// s a + t b = 1 for coprime a and b
+fn ext_gcd_mod(a: &ModPoly, b: &ModPoly, p: i64) -> (ModPoly, ModPoly) {
+    let (mut r0, mut r1) = (a.clone(), b.clone());
+    let (mut s0, mut s1) = (vec![1], vec![]);
+    let (mut t0, mut t1) = (vec![], vec![1]);
+
+    while !r1.is_empty() {
+        let (quot, rem) = div_rem_mod(&r0, &r1, p);
+        let s2 = sub_mod(&s0, &mul_mod(&quot, &s1, p), p);
+        let t2 = sub_mod(&t0, &mul_mod(&quot, &t1, p), p);
+
+        (r0, r1) = (r1, rem);
+        (s0, s1) = (s1, s2);
+        (t0, t1) = (t1, t2);
+    }
+
+    let inv = vec![pow_mod(r0[0], p - 2, p)];
+
+    (mul_mod(&s0, &inv, p), mul_mod(&t0, &inv, p))
+}
+
+fn derivative_mod(a: &ModPoly, p: i64) -> ModPoly {
+    trim_mod(
+        a.iter()
+            .enumerate()
+            .skip(1)
+            .map(|(i, c)| c * i as i64 % p)
+            .collect(),
+    )
+}
+
+fn pow_rem_mod(base: &ModPoly, mut exp: i64, m: &ModPoly, p: i64) -> ModPoly {
+    let mut res = vec![1];
+    let mut base = div_rem_mod(base, m, p).1;
+
+    while exp > 0 {
+        if exp % 2 == 1 {
+            res = div_rem_mod(&mul_mod(&res, &base, p), m, p).1;
+        }
+        base = div_rem_mod(&mul_mod(&base, &base, p), m, p).1;
+        exp /= 2;
+    }
+
+    res
+}
+
+// distinct degree factorization, then equal degree splitting
+fn factor_mod(f: &ModPoly, p: i64) -> Vec<ModPoly> {
+    let x = vec![0, 1];
+    let mut rest = f.clone();
+    let mut h = x.clone();
+    let mut factors = vec![];
+    let mut d = 1;
+
+    while rest.len() > 2 * d {
+        // h is x^(p^d) modulo rest
+        h = pow_rem_mod(&h, p, &rest, p);
+        let g = gcd_mod(&rest, &sub_mod(&h, &x, p), p);
+
+        if g.len() > 1 {
+            split_mod(&g, d, p, &mut factors);
+            rest = div_rem_mod(&rest, &g, p).0;
+            h = div_rem_mod(&h, &rest, p).1;
+        }
+
+        d += 1;
+    }
+
+    if rest.len() > 1 {
+        factors.push(rest);
+    }
+
+    factors
+}
+
+// cantor-zassenhaus on a product of distinct irreducibles of degree d
+fn split_mod(f: &ModPoly, d: usize, p: i64, factors: &mut Vec<ModPoly>) {
+    if f.len() - 1 == d {
+        factors.push(f.clone());
+        return;
+    }
+
+    let mut seed = f.len() as i64;
+
+    loop {
+        let t = trim_mod(
+            (0..f.len() - 1)
+                .map(|_| {
+                    seed = (seed * 1103515245 + 12345) % 2147483648;
+                    seed % p
+                })
+                .collect(),
+        );
+
+        if t.len() < 2 {
+            continue;
+        }
+
+        // t^((p^d - 1) / 2) as (t^(1 + p + ... + p^(d - 1)))^((p - 1) / 2)
+        let mut power = t.clone();
+        let mut norm = t;
+
+        for _ in 1..d {
+            power = pow_rem_mod(&power, p, f, p);
+            norm = div_rem_mod(&mul_mod(&norm, &power, p), f, p).1;
+        }
+
+        let g = gcd_mod(
+            f,
+            &sub_mod(&pow_rem_mod(&norm, (p - 1) / 2, f, p), &vec![1], p),
+            p,
+        );
+
+        if g.len() > 1 && g.len() < f.len() {
+            split_mod(&g, d, p, factors);
+            split_mod(&div_rem_mod(f, &g, p).0, d, p, factors);
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::poly::system::System;
+    use crate::rational::{Inexact, Rat};
+    use crate::univariate::{upoly, UPoly};
+
+    fn factors(src: &str) -> (Rat, Vec<(String, usize)>) {
+        let sys = System::parse(src).unwrap();
+        let (c, factors) = sys.members[0].factor().unwrap();
+
+        let mut factors = factors
+            .into_iter()
+            .map(|(p, multiplicity)| (p.format(&sys.var_dict), multiplicity))
+            .collect::<Vec<_>>();
+        factors.sort();
+
+        (c, factors)
+    }
+
+    #[test]
+    fn univariate() {
+        // 3(2x - 1)^2 (x^2 - 2)
+        let (c, factors) = upoly(&[12, -12, -21, 24, -6]).factor().unwrap();
+        assert_eq!(Rat::from(3), c);
+        assert_eq!(vec![(upoly(&[1, 0, -2]), 1), (upoly(&[2, -1]), 2)], factors);
+
+        // splits modulo every prime, but not over Q
+        assert_eq!(
+            vec![(upoly(&[1, 0, 0, 0, 1]), 1)],
+            upoly(&[1, 0, 0, 0, 1]).factor().unwrap().1
+        );
+
+        assert_eq!(
+            4,
+            upoly(&[1, 0, 0, 0, 0, 0, 0, 0, -1])
+                .factor()
+                .unwrap()
+                .1
+                .len()
+        );
+
+        // x / 2^40 + 1 / 3^25 = (3^25 x + 2^40) / (2^40 3^25), whose constant needs 80 bits
+        let (a, b) = (1i64 << 40, 3i64.pow(25));
+        let p = UPoly(vec![
+            Rat::from(1) / Rat::from(a),
+            Rat::from(1) / Rat::from(b),
+        ]);
+        assert_eq!(Err(Inexact), p.factor());
+    }
+
+    #[test]
+    fn multivariate() {
+        assert_eq!(
+            (
+                Rat::from(1),
+                vec![("x + y".to_string(), 1), ("x - y".to_string(), 1)]
+            ),
+            factors("x^2 - y^2")
+        );
+
+        assert_eq!(
+            vec![
+                ("x^2 + 2xy + 2y^2".to_string(), 1),
+                ("x^2 - 2xy + 2y^2".to_string(), 1)
+            ],
+            factors("x^4 + 4*y^4").1
+        );
+
+        assert_eq!(
+            (
+                Rat::from(-2),
+                vec![
+                    ("x + 2".to_string(), 1),
+                    ("x^2 + y^2 - 2".to_string(), 2),
+                    ("xy + 1".to_string(), 1),
+                    ("y".to_string(), 1),
+                ]
+            ),
+            factors("-2*y*(x*y + 1)*(x^2 + y^2 - 2)^2*(x + 2)")
+        );
+
+        assert_eq!(
+            vec![("xz + y".to_string(), 1), ("yz - x + 1".to_string(), 1)],
+            factors("(x*z + y)*(y*z - x + 1)").1
+        );
+
+        assert_eq!(
+            vec![("x^2y + z^2 + 1".to_string(), 1)],
+            factors("x^2*y + z^2 + 1").1
+        );
+        // every integer point in [-5, 5] makes the image a square
+        let (_, found) =
+            factors("x^2 - y*(y-1)*(y+1)*(y-2)*(y+2)*(y-3)*(y+3)*(y-4)*(y+4)*(y-5)*(y+5)");
+        assert_eq!(1, found.len());
+        assert_eq!(1, found[0].1);
+    }
+}
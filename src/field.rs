@@ -23,3 +23,66 @@ pub trait Zero {
 pub trait One {
     fn one() -> Self;
 }
+
+// fields of fractions, where any set of values has a common factor leaving coprime integers
+pub trait Fraction: Field {
+    // positive, and one for an empty or all zero set
+    fn primitive(vals: &[Self]) -> Self;
+}
+
+// largest integer not above x, found in T so that x may be of any size
+pub fn floor<T: Field>(x: &T) -> T {
+    let two = T::from(2);
+
+    // lo <= x < hi, with hi - lo a power of two
+    let (mut lo, mut hi) = if *x >= T::zero() {
+        let (mut lo, mut hi) = (T::zero(), T::one());
+        while hi <= *x {
+            lo = hi.clone();
+            hi = hi * two.clone();
+        }
+        (lo, hi)
+    } else {
+        let (mut lo, mut hi) = (T::from(-1), T::zero());
+        while lo > *x {
+            hi = lo.clone();
+            lo = lo * two.clone();
+        }
+        (lo, hi)
+    };
+
+    while hi.clone() - lo.clone() > T::one() {
+        let mid = lo.clone() + (hi.clone() - lo.clone()) / two.clone();
+        if mid <= *x {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+
+    lo
+}
+
+// the dyadic rational in the open interval (lo, hi) with the smallest denominator,
+// and among those the one closest to zero; keeps sample points cheap to evaluate
+pub fn simplest_between<T: Field>(lo: &T, hi: &T) -> T {
+    if *lo < T::zero() && *hi > T::zero() {
+        return T::zero();
+    }
+
+    if *hi <= T::zero() {
+        return simplest_between(&(hi.clone() * -1), &(lo.clone() * -1)) * -1;
+    }
+
+    let mut scale = T::one();
+
+    loop {
+        let candidate = (floor(&(lo.clone() * scale.clone())) + T::one()) / scale.clone();
+
+        if candidate < *hi {
+            return candidate;
+        }
+
+        scale = scale * T::from(2);
+    }
+}
@@ -0,0 +1,106 @@
+use std::cmp::{max, min, Ordering};
+use std::ops;
+
+use crate::field::Field;
+use crate::univariate::UPoly;
+
+// closed interval arithmetic, used to bound polynomial values near algebraic numbers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interval<T: Field> {
+    pub lo: T,
+    pub hi: T,
+}
+
+impl<T: Field> Interval<T> {
+    pub fn new(lo: T, hi: T) -> Self {
+        Interval { lo, hi }
+    }
+
+    pub fn point(val: T) -> Self {
+        Interval {
+            lo: val.clone(),
+            hi: val,
+        }
+    }
+
+    pub fn contains(&self, val: &T) -> bool {
+        self.lo <= *val && *val <= self.hi
+    }
+
+    pub fn overlaps(&self, other: &Interval<T>) -> bool {
+        self.lo <= other.hi && other.lo <= self.hi
+    }
+
+    // the sign of every value in the interval, if they all share one
+    pub fn sign(&self) -> Option<Ordering> {
+        if self.lo > T::zero() {
+            Some(Ordering::Greater)
+        } else if self.hi < T::zero() {
+            Some(Ordering::Less)
+        } else if self.lo.is_zero() && self.hi.is_zero() {
+            Some(Ordering::Equal)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: Field> ops::Add<Interval<T>> for Interval<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Interval {
+            lo: self.lo + rhs.lo,
+            hi: self.hi + rhs.hi,
+        }
+    }
+}
+
+impl<T: Field> ops::Mul<Interval<T>> for Interval<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let products = [
+            self.lo.clone() * rhs.lo.clone(),
+            self.lo * rhs.hi.clone(),
+            self.hi.clone() * rhs.lo,
+            self.hi * rhs.hi,
+        ];
+
+        Interval {
+            lo: products.iter().cloned().reduce(min).unwrap(),
+            hi: products.into_iter().reduce(max).unwrap(),
+        }
+    }
+}
+
+impl<T: Field> UPoly<T> {
+    // Horner's method; encloses every value of the polynomial over x
+    pub fn eval_interval(&self, x: &Interval<T>) -> Interval<T> {
+        self.0.iter().fold(Interval::point(T::zero()), |acc, next| {
+            acc * x.clone() + Interval::point(next.clone())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Interval;
+    use crate::rational::Rat;
+    use crate::univariate::UPoly;
+    use std::cmp::Ordering;
+
+    #[test]
+    fn enclosure() {
+        // x^2 - 2 over [1, 2]
+        let p = UPoly(vec![Rat::from(1), Rat::from(0), Rat::from(-2)]);
+        let val = p.eval_interval(&Interval::new(Rat::from(1), Rat::from(2)));
+
+        assert_eq!(val, Interval::new(Rat::from(-1), Rat::from(2)));
+        assert_eq!(val.sign(), None);
+
+        let val = p.eval_interval(&Interval::new(Rat::from(-3), Rat::from(-2)));
+
+        assert_eq!(val.sign(), Some(Ordering::Greater));
+    }
+}
@@ -3,6 +3,14 @@
 pub mod cad;
 pub mod poly;
 pub mod algebraic;
+pub mod bigint;
+pub mod bigrat;
+pub mod interval;
+pub mod parse;
 pub mod univariate;
 pub mod rational;
-pub mod field;
\ No newline at end of file
+pub mod field;
+pub mod factor;
+pub mod descartes;
+pub mod complex;
+pub mod stability;
\ No newline at end of file
@@ -0,0 +1,604 @@
+use std::fmt;
+
+use crate::cad::tarski::{Cmp, Constraint, Tarski, T};
+use crate::field::{One, Zero};
+use crate::poly::Poly;
+use crate::rational::{exact, Rat};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    UnexpectedChar(char),
+    UnexpectedToken { found: String, expected: &'static str },
+    UnexpectedEnd { expected: &'static str },
+    NumberTooLarge,
+    NonConstantDivisor,
+    DivisionByZero,
+    BadExponent,
+    ExistsAfterForall,
+}
+
+// pos is a byte offset into the source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub pos: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "at {}: ", self.pos)?;
+
+        match &self.kind {
+            ParseErrorKind::UnexpectedChar(c) => write!(f, "unexpected character '{c}'"),
+            ParseErrorKind::UnexpectedToken { found, expected } => {
+                write!(f, "expected {expected}, found '{found}'")
+            }
+            ParseErrorKind::UnexpectedEnd { expected } => {
+                write!(f, "expected {expected}, found end of input")
+            }
+            ParseErrorKind::NumberTooLarge => write!(f, "number does not fit in a rational"),
+            ParseErrorKind::NonConstantDivisor => write!(f, "can only divide by constants"),
+            ParseErrorKind::DivisionByZero => write!(f, "division by zero"),
+            ParseErrorKind::BadExponent => {
+                write!(f, "exponent must be an integer from 0 to {MAX_EXPONENT}")
+            }
+            ParseErrorKind::ExistsAfterForall => {
+                write!(f, "exists blocks must come before forall blocks")
+            }
+        }
+    }
+}
+
+// far past any degree the solver can handle, but small enough to expand without hanging
+const MAX_EXPONENT: i64 = 1000;
+
+const KEYWORDS: [&str; 7] = ["exists", "forall", "and", "or", "not", "true", "false"];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Tok {
+    Num(Rat),
+    Ident(String),
+    Cmp(Cmp),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Caret,
+    LParen,
+    RParen,
+    Dot,
+    Comma,
+}
+
+impl fmt::Display for Tok {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Tok::Num(n) => write!(f, "{n}"),
+            Tok::Ident(name) => write!(f, "{name}"),
+            Tok::Cmp(cmp) => write!(f, "{cmp}"),
+            Tok::Plus => write!(f, "+"),
+            Tok::Minus => write!(f, "-"),
+            Tok::Star => write!(f, "*"),
+            Tok::Slash => write!(f, "/"),
+            Tok::Caret => write!(f, "^"),
+            Tok::LParen => write!(f, "("),
+            Tok::RParen => write!(f, ")"),
+            Tok::Dot => write!(f, "."),
+            Tok::Comma => write!(f, ","),
+        }
+    }
+}
+
+// digits with an optional decimal part, exactly as a rational
+fn number(text: &str, pos: usize) -> Result<Rat, ParseError> {
+    let too_large = ParseError {
+        pos,
+        kind: ParseErrorKind::NumberTooLarge,
+    };
+
+    let decimals = text.split_once('.').map_or(0, |(_, frac)| frac.len());
+    let num = text.replace('.', "").parse::<i64>().map_err(|_| too_large.clone())?;
+    let den = 10i64.checked_pow(decimals as u32).ok_or(too_large)?;
+
+    Ok(Rat::from(num) / Rat::from(den))
+}
+
+fn tokenize(src: &str) -> Result<Vec<(Tok, usize)>, ParseError> {
+    let mut tokens = vec![];
+    let chars = src.char_indices().collect::<Vec<_>>();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let (pos, c) = chars[i];
+        let next = chars.get(i + 1).map(|(_, c)| *c);
+
+        let (tok, len) = match (c, next) {
+            (c, _) if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            (c, _) if c.is_ascii_digit() => {
+                let mut end = i;
+                while end < chars.len() && chars[end].1.is_ascii_digit() {
+                    end += 1;
+                }
+
+                // a dot is only part of the number when digits follow
+                if end + 1 < chars.len() && chars[end].1 == '.' && chars[end + 1].1.is_ascii_digit() {
+                    end += 1;
+                    while end < chars.len() && chars[end].1.is_ascii_digit() {
+                        end += 1;
+                    }
+                }
+
+                let text = chars[i..end].iter().map(|(_, c)| c).collect::<String>();
+                (Tok::Num(number(&text, pos)?), end - i)
+            }
+            (c, _) if c.is_alphabetic() || c == '_' => {
+                let mut end = i;
+                while end < chars.len() && (chars[end].1.is_alphanumeric() || chars[end].1 == '_') {
+                    end += 1;
+                }
+
+                let text = chars[i..end].iter().map(|(_, c)| c).collect::<String>();
+                (Tok::Ident(text), end - i)
+            }
+            ('>', Some('=')) => (Tok::Cmp(Cmp::Ge), 2),
+            ('<', Some('=')) => (Tok::Cmp(Cmp::Le), 2),
+            ('!', Some('=')) => (Tok::Cmp(Cmp::Ne), 2),
+            ('=', Some('=')) => (Tok::Cmp(Cmp::Eq), 2),
+            ('>', _) => (Tok::Cmp(Cmp::Gt), 1),
+            ('<', _) => (Tok::Cmp(Cmp::Lt), 1),
+            ('=', _) => (Tok::Cmp(Cmp::Eq), 1),
+            ('+', _) => (Tok::Plus, 1),
+            ('-', _) => (Tok::Minus, 1),
+            ('*', _) => (Tok::Star, 1),
+            ('/', _) => (Tok::Slash, 1),
+            ('^', _) => (Tok::Caret, 1),
+            ('(', _) => (Tok::LParen, 1),
+            (')', _) => (Tok::RParen, 1),
+            ('.', _) => (Tok::Dot, 1),
+            (',', _) => (Tok::Comma, 1),
+            (c, _) => {
+                return Err(ParseError {
+                    pos,
+                    kind: ParseErrorKind::UnexpectedChar(c),
+                })
+            }
+        };
+
+        tokens.push((tok, pos));
+        i += len;
+    }
+
+    Ok(tokens)
+}
+
+// recursive descent over
+//   system     := (expr ("," expr)* ","?)?
+//   tarski     := (("exists" | "forall") ident ("," ident)* ".")* disjunction
+//   disjunction := conjunction ("or" conjunction)*
+//   conjunction := negation ("and" negation)*
+//   negation   := "not" negation | "true" | "false" | expr cmp expr | "(" disjunction ")"
+//   expr       := term (("+" | "-") term)*
+//   term       := unary (("*" | "/") unary | number-prefixed juxtaposition)*
+//   unary      := "-" unary | power
+//   power      := primary ("^" integer)?
+//   primary    := number | ident | "(" expr ")"
+pub struct Parser {
+    tokens: Vec<(Tok, usize)>,
+    at: usize,
+    end: usize,
+    pub var_dict: Vec<String>,
+}
+
+impl Parser {
+    pub fn new(src: &str, var_dict: Vec<String>) -> Result<Self, ParseError> {
+        Ok(Parser {
+            tokens: tokenize(src)?,
+            at: 0,
+            end: src.len(),
+            var_dict,
+        })
+    }
+
+    fn peek(&self) -> Option<&Tok> {
+        self.tokens.get(self.at).map(|(tok, _)| tok)
+    }
+
+    fn pos(&self) -> usize {
+        self.tokens.get(self.at).map_or(self.end, |(_, pos)| *pos)
+    }
+
+    fn keyword(&self, word: &str) -> bool {
+        matches!(self.peek(), Some(Tok::Ident(name)) if name == word)
+    }
+
+    fn unexpected(&self, expected: &'static str) -> ParseError {
+        ParseError {
+            pos: self.pos(),
+            kind: match self.peek() {
+                Some(tok) => ParseErrorKind::UnexpectedToken {
+                    found: tok.to_string(),
+                    expected,
+                },
+                None => ParseErrorKind::UnexpectedEnd { expected },
+            },
+        }
+    }
+
+    fn expect(&mut self, tok: Tok, expected: &'static str) -> Result<(), ParseError> {
+        if self.peek() == Some(&tok) {
+            self.at += 1;
+            Ok(())
+        } else {
+            Err(self.unexpected(expected))
+        }
+    }
+
+    fn var_name(&mut self) -> Result<String, ParseError> {
+        match self.peek() {
+            Some(Tok::Ident(name)) if !KEYWORDS.contains(&name.as_str()) => {
+                let name = name.clone();
+                self.at += 1;
+                Ok(name)
+            }
+            _ => Err(self.unexpected("variable")),
+        }
+    }
+
+    fn var_index(&mut self, name: String) -> usize {
+        match self.var_dict.iter().position(|v| *v == name) {
+            Some(i) => i,
+            None => {
+                self.var_dict.push(name);
+                self.var_dict.len() - 1
+            }
+        }
+    }
+
+    pub fn finish(&self) -> Result<(), ParseError> {
+        match self.peek() {
+            Some(_) => Err(self.unexpected("end of input")),
+            None => Ok(()),
+        }
+    }
+
+    pub fn expr(&mut self) -> Result<Poly<Rat>, ParseError> {
+        let mut value = self.term()?;
+
+        loop {
+            match self.peek() {
+                Some(Tok::Plus) => {
+                    self.at += 1;
+                    value = value + self.term()?;
+                }
+                Some(Tok::Minus) => {
+                    self.at += 1;
+                    value = value - self.term()?;
+                }
+                _ => return Ok(value),
+            }
+        }
+    }
+
+    fn term(&mut self) -> Result<Poly<Rat>, ParseError> {
+        let mut value = self.unary()?;
+
+        loop {
+            let after_number = matches!(self.tokens[self.at - 1].0, Tok::Num(_));
+
+            match self.peek() {
+                Some(Tok::Star) => {
+                    self.at += 1;
+                    value = value * self.unary()?;
+                }
+                Some(Tok::Slash) => {
+                    self.at += 1;
+                    let pos = self.pos();
+                    let divisor = self.unary()?;
+
+                    let divisor = match divisor.terms.as_slice() {
+                        [] => {
+                            return Err(ParseError {
+                                pos,
+                                kind: ParseErrorKind::DivisionByZero,
+                            })
+                        }
+                        [term] if term.vars.is_empty() => term.val,
+                        _ => {
+                            return Err(ParseError {
+                                pos,
+                                kind: ParseErrorKind::NonConstantDivisor,
+                            })
+                        }
+                    };
+
+                    value = value * Poly::constant(Rat::one() / divisor);
+                }
+                // 3x and 2(x + 1)
+                Some(Tok::LParen) if after_number => value = value * self.unary()?,
+                Some(Tok::Ident(name)) if after_number && !KEYWORDS.contains(&name.as_str()) => {
+                    value = value * self.unary()?
+                }
+                _ => return Ok(value),
+            }
+        }
+    }
+
+    fn unary(&mut self) -> Result<Poly<Rat>, ParseError> {
+        if self.peek() == Some(&Tok::Minus) {
+            self.at += 1;
+            Ok(Poly::constant(Rat::zero()) - self.unary()?)
+        } else {
+            self.power()
+        }
+    }
+
+    fn power(&mut self) -> Result<Poly<Rat>, ParseError> {
+        let base = self.primary()?;
+
+        if self.peek() != Some(&Tok::Caret) {
+            return Ok(base);
+        }
+
+        self.at += 1;
+
+        let pos = self.pos();
+        let exp = match self.peek() {
+            Some(Tok::Num(n)) => n.try_int().filter(|exp| (0..=MAX_EXPONENT).contains(exp)),
+            _ => None,
+        };
+
+        let Some(exp) = exp else {
+            return Err(ParseError {
+                pos,
+                kind: ParseErrorKind::BadExponent,
+            });
+        };
+
+        self.at += 1;
+
+        // a rounded coefficient would silently change the polynomial
+        (0..exp).try_fold(Poly::constant(Rat::one()), |acc, _| {
+            exact(|| acc * base.clone()).map_err(|_| ParseError {
+                pos,
+                kind: ParseErrorKind::NumberTooLarge,
+            })
+        })
+    }
+
+    fn primary(&mut self) -> Result<Poly<Rat>, ParseError> {
+        match self.peek() {
+            Some(Tok::Num(n)) => {
+                let n = *n;
+                self.at += 1;
+                Ok(Poly::constant(n))
+            }
+            Some(Tok::Ident(name)) if !KEYWORDS.contains(&name.as_str()) => {
+                let name = self.var_name()?;
+                Ok(Poly::var(self.var_index(name), 1))
+            }
+            Some(Tok::LParen) => {
+                self.at += 1;
+                let value = self.expr()?;
+                self.expect(Tok::RParen, "')'")?;
+                Ok(value)
+            }
+            _ => Err(self.unexpected("expression")),
+        }
+    }
+
+    // comma separated, with an optional trailing comma
+    pub fn poly_list(&mut self) -> Result<Vec<Poly<Rat>>, ParseError> {
+        let mut polys = vec![];
+
+        while self.peek().is_some() {
+            polys.push(self.expr()?);
+
+            if self.peek() == Some(&Tok::Comma) {
+                self.at += 1;
+            } else {
+                break;
+            }
+        }
+
+        self.finish()?;
+
+        Ok(polys)
+    }
+
+    fn constraint(&mut self) -> Result<T, ParseError> {
+        let lhs = self.expr()?;
+
+        let cmp_zero = match self.peek() {
+            Some(Tok::Cmp(cmp)) => *cmp,
+            _ => return Err(self.unexpected("comparison")),
+        };
+
+        self.at += 1;
+        let rhs = self.expr()?;
+
+        Ok(T::C(Constraint {
+            value: lhs - rhs,
+            cmp_zero,
+        }))
+    }
+
+    fn negation(&mut self) -> Result<T, ParseError> {
+        if self.keyword("not") {
+            self.at += 1;
+            return Ok(T::Not(Box::new(self.negation()?)));
+        } else if self.keyword("true") {
+            self.at += 1;
+            return Ok(T::True);
+        } else if self.keyword("false") {
+            self.at += 1;
+            return Ok(T::False);
+        } else if self.peek() != Some(&Tok::LParen) {
+            return self.constraint();
+        }
+
+        // a parenthesis opens either a subformula or a polynomial like (x + 1)^2 > 0
+        let (at, n_vars) = (self.at, self.var_dict.len());
+
+        let as_constraint = match self.constraint() {
+            Ok(t) => return Ok(t),
+            Err(e) => e,
+        };
+
+        self.at = at;
+        self.var_dict.truncate(n_vars);
+
+        let as_formula = self.expect(Tok::LParen, "'('").and_then(|_| {
+            let inner = self.disjunction()?;
+            self.expect(Tok::RParen, "')'")?;
+            Ok(inner)
+        });
+
+        // report whichever reading got further
+        as_formula.map_err(|e| if e.pos >= as_constraint.pos { e } else { as_constraint })
+    }
+
+    fn conjunction(&mut self) -> Result<T, ParseError> {
+        let mut value = self.negation()?;
+
+        while self.keyword("and") {
+            self.at += 1;
+            value = T::And(Box::new(value), Box::new(self.negation()?));
+        }
+
+        Ok(value)
+    }
+
+    pub fn disjunction(&mut self) -> Result<T, ParseError> {
+        let mut value = self.conjunction()?;
+
+        while self.keyword("or") {
+            self.at += 1;
+            value = T::Or(Box::new(value), Box::new(self.conjunction()?));
+        }
+
+        Ok(value)
+    }
+
+    pub fn tarski(&mut self) -> Result<Tarski, ParseError> {
+        let (mut exists, mut forall) = (vec![], vec![]);
+
+        while self.keyword("exists") || self.keyword("forall") {
+            let is_exists = self.keyword("exists");
+
+            if is_exists && !forall.is_empty() {
+                return Err(ParseError {
+                    pos: self.pos(),
+                    kind: ParseErrorKind::ExistsAfterForall,
+                });
+            }
+
+            self.at += 1;
+
+            loop {
+                let name = self.var_name()?;
+                let var = self.var_index(name);
+
+                if is_exists {
+                    exists.push(var);
+                } else {
+                    forall.push(var);
+                }
+
+                if self.peek() == Some(&Tok::Comma) {
+                    self.at += 1;
+                } else {
+                    break;
+                }
+            }
+
+            self.expect(Tok::Dot, "'.'")?;
+        }
+
+        let data = self.disjunction()?;
+        self.finish()?;
+
+        Ok(Tarski {
+            var_dict: self.var_dict.clone(),
+            exists,
+            forall,
+            data,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ParseError, ParseErrorKind};
+    use crate::cad::tarski::{Tarski, T};
+
+    #[test]
+    fn formulas() {
+        let formula =
+            Tarski::parse("exists x. forall y. (x^2 + y > 0 and not (x = y)) or y <= 3").unwrap();
+
+        assert_eq!(formula.var_dict, vec!["x", "y"]);
+        assert_eq!(formula.exists, vec![0]);
+        assert_eq!(formula.forall, vec![1]);
+        assert_eq!(
+            "exists x. forall y. x^2 + y > 0 and not (x - y = 0) or y - 3 <= 0",
+            formula.to_string()
+        );
+
+        // printing parses back to the same formula
+        let reparsed = Tarski::parse(&formula.to_string()).unwrap();
+        assert_eq!(formula.to_string(), reparsed.to_string());
+
+        let formula = Tarski::parse("exists a, b. (a + 1)^2 * (b - 1/2) >= -3/4a or true").unwrap();
+        assert_eq!(
+            "exists a, b. a^2b - 1/2a^2 + 2ab - 1/4a + b - 1/2 >= 0 or true",
+            formula.to_string()
+        );
+
+        // coefficients that f64 cannot hold exactly survive the round trip
+        let formula = Tarski::parse("x^2 - x/3 + 2/3 > 0 or x = 1/7").unwrap();
+        assert_eq!("x^2 - 1/3x + 2/3 > 0 or x - 1/7 = 0", formula.to_string());
+        assert_eq!(formula.to_string(), Tarski::parse(&formula.to_string()).unwrap().to_string());
+
+        let formula = Tarski::parse("((x > 0)) and -(-x) != 2(x - 0.5)").unwrap();
+        assert!(matches!(formula.data, T::And(_, _)));
+        assert_eq!("x > 0 and -x + 1 != 0", formula.to_string());
+    }
+
+    #[test]
+    fn errors() {
+        let err = |src: &str| Tarski::parse(src).unwrap_err();
+
+        assert_eq!(
+            err("x + > 0"),
+            ParseError {
+                pos: 4,
+                kind: ParseErrorKind::UnexpectedToken {
+                    found: ">".to_string(),
+                    expected: "expression"
+                }
+            }
+        );
+
+        assert_eq!(
+            err("exists x x > 0").kind,
+            ParseErrorKind::UnexpectedToken {
+                found: "x".to_string(),
+                expected: "'.'"
+            }
+        );
+
+        assert_eq!(err("(x > 0").pos, 6);
+        assert_eq!(err("x # 1").kind, ParseErrorKind::UnexpectedChar('#'));
+        assert_eq!(err("x / y > 0").kind, ParseErrorKind::NonConstantDivisor);
+        assert_eq!(err("x / (1 - 1) > 0").kind, ParseErrorKind::DivisionByZero);
+        assert_eq!(err("x^y > 0").kind, ParseErrorKind::BadExponent);
+        assert_eq!(err("x^99999999999 > 0").kind, ParseErrorKind::BadExponent);
+        assert_eq!(err("(x + 3)^60 > 0").kind, ParseErrorKind::NumberTooLarge);
+        assert_eq!(err("forall x. exists y. x = y").kind, ParseErrorKind::ExistsAfterForall);
+        assert_eq!(err("x > 99999999999999999999").kind, ParseErrorKind::NumberTooLarge);
+        assert_eq!(err("x > 0 and").to_string(), "at 9: expected expression, found end of input");
+    }
+}
@@ -0,0 +1,182 @@
+use std::collections::{HashMap, HashSet};
+
+use super::mono::{grevlex, monomial_div, monomial_mul, Mono, MonomialOrder};
+use super::system::{add_member, norm_in, Pair};
+use super::Poly;
+use crate::field::Fraction;
+
+// sparse row, ascending column, where column 0 is the largest monomial
+type Row<T> = Vec<(usize, T)>;
+
+fn total_deg<T: Fraction>(m: &Mono<T>) -> u64 {
+    m.vars.iter().map(|(_, pow)| pow).sum()
+}
+
+fn shifted<T: Fraction>(vars: Vec<(usize, u64)>, p: &Poly<T>) -> Poly<T> {
+    let m = Mono {
+        val: T::one(),
+        vars,
+    };
+
+    Poly {
+        terms: p.terms.iter().map(|term| monomial_mul(&m, term)).collect(),
+    }
+}
+
+// a * lhs - b * rhs, made primitive again so coefficients stay small
+fn combine<T: Fraction>(a: &T, lhs: &Row<T>, b: &T, rhs: &Row<T>) -> Row<T> {
+    let mut res = Vec::with_capacity(lhs.len() + rhs.len());
+    let (mut l, mut r) = (lhs.iter().peekable(), rhs.iter().peekable());
+
+    loop {
+        match (l.peek(), r.peek()) {
+            (Some((lc, lv)), Some((rc, rv))) if lc == rc => {
+                let val = a.clone() * lv.clone() - b.clone() * rv.clone();
+                if !val.is_zero() {
+                    res.push((*lc, val));
+                }
+                l.next();
+                r.next();
+            }
+            (Some((lc, lv)), Some((rc, _))) if lc < rc => {
+                res.push((*lc, a.clone() * lv.clone()));
+                l.next();
+            }
+            (_, Some((rc, rv))) => {
+                res.push((*rc, b.clone() * rv.clone() * -1));
+                r.next();
+            }
+            (Some((lc, lv)), None) => {
+                res.push((*lc, a.clone() * lv.clone()));
+                l.next();
+            }
+            (None, None) => return primitive(res),
+        }
+    }
+}
+
+fn primitive<T: Fraction>(row: Row<T>) -> Row<T> {
+    let scale = T::primitive(&row.iter().map(|(_, v)| v.clone()).collect::<Vec<_>>());
+    row.into_iter()
+        .map(|(c, v)| (c, v / scale.clone()))
+        .collect()
+}
+
+// one f4 round: reduce every pair of the lowest lcm degree at once with a macaulay matrix
+pub(super) fn step<T: Fraction>(
+    members: &mut Vec<Poly<T>>,
+    pairs: &mut Vec<Pair<T>>,
+    order: &MonomialOrder,
+) {
+    let deg = pairs.iter().map(|pair| total_deg(&pair.lcm)).min().unwrap();
+    let (selected, rest) = std::mem::take(pairs)
+        .into_iter()
+        .partition::<Vec<_>, _>(|pair| total_deg(&pair.lcm) == deg);
+    *pairs = rest;
+
+    let one = |vars: &Vec<(usize, u64)>| Mono {
+        val: T::one(),
+        vars: vars.clone(),
+    };
+    let cofactor =
+        |lcm: &Mono<T>, p: &Poly<T>| monomial_div(lcm, &one(&p.lt_mono_in(order).vars)).unwrap().vars;
+
+    let mut rows = vec![];
+    let mut done = HashSet::new();
+
+    for Pair { i, j, lcm } in &selected {
+        rows.push(shifted(cofactor(lcm, &members[*i]), &members[*i]));
+        rows.push(shifted(cofactor(lcm, &members[*j]), &members[*j]));
+        done.insert(lcm.vars.clone());
+    }
+
+    // symbolic preprocessing: add a reducer for every reducible monomial that appears
+    let mut next = 0;
+
+    while next < rows.len() {
+        let monos = rows[next]
+            .terms
+            .iter()
+            .map(|term| term.vars.clone())
+            .collect::<Vec<_>>();
+        next += 1;
+
+        for vars in monos {
+            if !done.insert(vars.clone()) {
+                continue;
+            }
+
+            let m = one(&vars);
+
+            if let Some(g) = members
+                .iter()
+                .find(|g| monomial_div(&m, &one(&g.lt_mono_in(order).vars)).is_some())
+            {
+                rows.push(shifted(cofactor(&m, g), g));
+            }
+        }
+    }
+
+    let mut monos = rows
+        .iter()
+        .flat_map(|row| row.terms.iter().map(|term| one(&term.vars)))
+        .collect::<Vec<_>>();
+    monos.sort_by(|a, b| order.cmp(b, a));
+    monos.dedup();
+
+    let cols = monos
+        .iter()
+        .enumerate()
+        .map(|(i, m)| (m.vars.clone(), i))
+        .collect::<HashMap<_, _>>();
+
+    let leading = rows
+        .iter()
+        .map(|row| cols[&row.lt_mono_in(order).vars])
+        .collect::<HashSet<_>>();
+
+    // fraction-free row echelon form
+    let mut pivots: HashMap<usize, Row<T>> = HashMap::new();
+    let mut pivot_order = vec![];
+
+    // reducers first, as their leading columns are already distinct
+    let pair_rows = selected.len() * 2;
+
+    for row in rows[pair_rows..].iter().chain(&rows[..pair_rows]) {
+        let mut row: Row<T> = row
+            .terms
+            .iter()
+            .map(|term| (cols[&term.vars], term.val.clone()))
+            .collect();
+        row.sort_by_key(|(col, _)| *col);
+
+        while let Some(pivot) = row.first().and_then(|(col, _)| pivots.get(col)) {
+            row = combine(&pivot[0].1, &row, &row[0].1, pivot);
+        }
+
+        if let Some((col, _)) = row.first() {
+            pivot_order.push(*col);
+            pivots.insert(*col, primitive(row));
+        }
+    }
+
+    // rows whose leading monomial is not one we started with are new basis elements
+    for col in pivot_order {
+        if leading.contains(&col) {
+            continue;
+        }
+
+        let mut terms = pivots[&col]
+            .iter()
+            .map(|(c, v)| Mono {
+                val: v.clone(),
+                vars: monos[*c].vars.clone(),
+            })
+            .collect::<Vec<_>>();
+        terms.sort_by(grevlex);
+
+        let p = Poly { terms };
+
+        add_member(members, pairs, norm_in(&p, order), order);
+    }
+}
@@ -0,0 +1,186 @@
+use crate::rational::Rat;
+use crate::univariate::UPoly;
+
+// numerator(t) / (1 - t)^vars, the generating function of the number of standard
+// monomials in each degree; numerator coefficients are by ascending power, and sums of
+// signs over subsets of the generators, so they stay far below i64 long before the
+// subset recursion finishes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HilbertSeries {
+    pub numerator: Vec<i64>,
+    pub vars: usize,
+}
+
+impl HilbertSeries {
+    // the series of the quotient by a monomial ideal, from exponent vectors of its generators
+    pub fn of_monomials(gens: &[Vec<u64>], vars: usize) -> Self {
+        HilbertSeries {
+            numerator: trim(numerator(gens.to_vec())),
+            vars,
+        }
+    }
+
+    // with every factor of 1 - t cancelled from the numerator
+    pub fn reduced(&self) -> Self {
+        let mut numerator = self.numerator.clone();
+        let mut vars = self.vars;
+
+        while vars > 0 && !numerator.is_empty() && numerator.iter().sum::<i64>() == 0 {
+            // synthetic division by 1 - t
+            let mut acc = 0;
+            numerator = numerator[..numerator.len() - 1]
+                .iter()
+                .map(|c| {
+                    acc += c;
+                    acc
+                })
+                .collect();
+            vars -= 1;
+        }
+
+        HilbertSeries { numerator, vars }
+    }
+
+    // the order of the pole at t = 1; None for the zero series of the unit ideal
+    pub fn dimension(&self) -> Option<usize> {
+        let reduced = self.reduced();
+
+        if reduced.numerator.is_empty() {
+            None
+        } else {
+            Some(reduced.vars)
+        }
+    }
+
+    // the number of standard monomials of degree d; None when it does not fit in i64
+    pub fn coefficient(&self, d: usize) -> Option<i64> {
+        self.numerator
+            .iter()
+            .enumerate()
+            .take(d + 1)
+            .try_fold(0i64, |acc, (i, c)| {
+                acc.checked_add(c.checked_mul(monomials(d - i, self.vars)?)?)
+            })
+    }
+
+    // agrees with coefficient(d) for every d past the numerator's degree
+    pub fn polynomial(&self) -> UPoly<Rat> {
+        let HilbertSeries { numerator, vars } = self.reduced();
+
+        if vars == 0 {
+            return UPoly(vec![]);
+        }
+
+        // sum of q_i C(d - i + vars - 1, vars - 1)
+        let mut total = UPoly(vec![]);
+
+        for (i, q) in numerator.iter().enumerate() {
+            let mut term = UPoly(vec![Rat::from(*q)]);
+
+            for j in 1..vars {
+                term = term
+                    * UPoly(vec![Rat::from(1), Rat::from(j as i64 - i as i64)])
+                    * UPoly(vec![Rat::from(1) / Rat::from(j as i64)]);
+            }
+
+            total = total + term;
+        }
+
+        total.trim()
+    }
+}
+
+// the number of monomials of degree d in k variables, C(d + k - 1, k - 1); None when it
+// does not fit in i64
+fn monomials(d: usize, k: usize) -> Option<i64> {
+    if k == 0 {
+        return Some(i64::from(d == 0));
+    }
+
+    // each step is C(d + j, j), exact; the product before dividing fits in i128
+    (1..k).try_fold(1i64, |acc, j| {
+        i64::try_from(acc as i128 * (d + j) as i128 / j as i128).ok()
+    })
+}
+
+fn trim(mut p: Vec<i64>) -> Vec<i64> {
+    while p.last() == Some(&0) {
+        p.pop();
+    }
+    p
+}
+
+fn sub(lhs: Vec<i64>, rhs: Vec<i64>) -> Vec<i64> {
+    let len = lhs.len().max(rhs.len());
+    (0..len)
+        .map(|i| lhs.get(i).unwrap_or(&0) - rhs.get(i).unwrap_or(&0))
+        .collect()
+}
+
+fn divides(a: &[u64], b: &[u64]) -> bool {
+    a.iter().zip(b).all(|(a, b)| a <= b)
+}
+
+// hilbert series numerator of the quotient by the monomial ideal, by
+// N(I + <m>) = N(I) - t^deg(m) N(I : m)
+fn numerator(mut gens: Vec<Vec<u64>>) -> Vec<i64> {
+    // drop generators that a different or an earlier equal one divides
+    let all = gens.clone();
+    let mut i = 0;
+    gens.retain(|g| {
+        i += 1;
+        !all.iter()
+            .enumerate()
+            .any(|(j, h)| divides(h, g) && (h != g || j + 1 < i))
+    });
+
+    let Some(m) = gens.pop() else {
+        return vec![1];
+    };
+
+    let deg = m.iter().sum::<u64>() as usize;
+    let colon = gens
+        .iter()
+        .map(|g| g.iter().zip(&m).map(|(g, m)| g.saturating_sub(*m)).collect())
+        .collect();
+
+    let mut shifted = vec![0; deg];
+    shifted.extend(numerator(colon));
+
+    sub(numerator(gens), shifted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HilbertSeries;
+    use crate::rational::Rat;
+    use crate::univariate::UPoly;
+
+    #[test]
+    fn series() {
+        // <xz, yz>: a plane and a line
+        let series = HilbertSeries::of_monomials(&[vec![1, 0, 1], vec![0, 1, 1]], 3);
+
+        assert_eq!(vec![1, 0, -2, 1], series.numerator);
+        assert_eq!(vec![1, 1, -1], series.reduced().numerator);
+        assert_eq!(Some(2), series.dimension());
+        assert_eq!(UPoly(vec![Rat::from(1), Rat::from(2)]), series.polynomial());
+        assert_eq!(
+            vec![Some(1), Some(3), Some(4), Some(5), Some(6)],
+            (0..5).map(|d| series.coefficient(d)).collect::<Vec<_>>()
+        );
+
+        // C(500 + 9, 9) fits, though the products along the way need more than 64 bits,
+        // and the number of monomials of degree 1000 in 40 variables does not
+        let free = HilbertSeries::of_monomials(&[], 10);
+        assert_eq!(Some(5_885_837_674_864_462_601), free.coefficient(500));
+        assert_eq!(None, HilbertSeries::of_monomials(&[], 40).coefficient(1000));
+
+        // <x^2, y^3> has six standard monomials
+        let series = HilbertSeries::of_monomials(&[vec![2, 0], vec![0, 3]], 2);
+        assert_eq!(Some(0), series.dimension());
+        assert_eq!(6, series.reduced().numerator.iter().sum::<i64>());
+
+        assert_eq!(None, HilbertSeries::of_monomials(&[vec![0, 0]], 2).dimension());
+    }
+}
@@ -120,7 +120,8 @@ macro_rules! system {
 
         System {
             members: vec![],
-            var_dict: Rc::new(vec![])
+            var_dict: Rc::new(vec![]),
+            order: $crate::poly::mono::MonomialOrder::Grlex
         }
     }};
 
@@ -173,7 +174,8 @@ macro_rules! system {
 
                     acc
                 })
-                .collect::<Vec<_>>()
+                .collect::<Vec<_>>(),
+            order: $crate::poly::mono::MonomialOrder::Grlex
         }
     }};
 
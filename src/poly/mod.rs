@@ -1,3 +1,5 @@
+mod f4;
+pub mod hilbert;
 pub mod macros;
 pub mod mono;
 pub mod poly_arithmetic;
@@ -6,9 +8,10 @@ pub mod system;
 use std::fmt::Write;
 
 use crate::poly::mono::*;
-use crate::rational::{gcd, Rat};
+use crate::rational::Rat;
 
-use crate::field::Field;
+use crate::field::{Field, Fraction};
+use crate::parse::{ParseError, Parser};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Poly<T: Field> {
@@ -16,6 +19,17 @@ pub struct Poly<T: Field> {
 }
 
 impl Poly<Rat> {
+    // variables missing from var_dict are appended to it
+    pub fn parse(src: &str, var_dict: &mut Vec<String>) -> Result<Poly<Rat>, ParseError> {
+        let mut parser = Parser::new(src, var_dict.clone())?;
+        let p = parser.expr()?;
+        parser.finish()?;
+
+        *var_dict = parser.var_dict;
+
+        Ok(p)
+    }
+
     pub fn get_constant_val(&self) -> Option<i64> {
         if self.terms.is_empty() {
             Some(0)
@@ -73,6 +87,26 @@ impl<T: Field> Poly<T> {
         }
     }
 
+    // scaled so the leading coefficient is one
+    pub fn monic(&self) -> Poly<T> {
+        match self.terms.last() {
+            Some(m) => {
+                let lc = m.val.clone();
+                Poly {
+                    terms: self
+                        .terms
+                        .iter()
+                        .map(|term| Mono {
+                            val: term.val.clone() / lc.clone(),
+                            vars: term.vars.clone(),
+                        })
+                        .collect(),
+                }
+            }
+            None => Poly { terms: vec![] },
+        }
+    }
+
     pub fn lt_mono(&self) -> Mono<T> {
         match self.terms.last() {
             Some(m) => m.clone(),
@@ -83,6 +117,35 @@ impl<T: Field> Poly<T> {
         }
     }
 
+    // position of the leading term under order
+    fn lt_index_in(&self, order: &MonomialOrder) -> Option<usize> {
+        match order {
+            MonomialOrder::Grlex => self.terms.len().checked_sub(1),
+            _ => (0..self.terms.len()).max_by(|&a, &b| order.cmp(&self.terms[a], &self.terms[b])),
+        }
+    }
+
+    pub fn lt_mono_in(&self, order: &MonomialOrder) -> Mono<T> {
+        match self.lt_index_in(order) {
+            Some(i) => self.terms[i].clone(),
+            None => self.lt_mono(),
+        }
+    }
+
+    pub fn s_poly_in(p: Poly<T>, q: Poly<T>, order: &MonomialOrder) -> Poly<T> {
+        let (p_lt, q_lt) = (p.lt_mono_in(order), q.lt_mono_in(order));
+        let lcm = monomial_lcm(p_lt.clone(), q_lt.clone());
+
+        let coef_p = Poly {
+            terms: vec![monomial_div(&lcm, &p_lt).unwrap()],
+        };
+        let coef_q = Poly {
+            terms: vec![monomial_div(&lcm, &q_lt).unwrap()],
+        };
+
+        coef_p * p - coef_q * q
+    }
+
     pub fn s_poly(p: Poly<T>, q: Poly<T>) -> Poly<T> {
         let p_lt = p.lt();
         let q_lt = q.lt();
@@ -109,9 +172,7 @@ impl<T: Field> Poly<T> {
 
     pub fn coefs(&self, var: usize) -> Vec<Poly<T>> {
         let deg = self.deg(var);
-        let mut coefs: Vec<_> = std::iter::repeat(Poly::constant(T::zero()))
-            .take(deg + 1)
-            .collect();
+        let mut coefs = vec![Poly::constant(T::zero()); deg + 1];
 
         for term in self.terms.iter().rev() {
             let (term_deg, term_coef) = term.coef(var);
@@ -142,6 +203,21 @@ impl<T: Field> Poly<T> {
         new
     }
 
+    // the same polynomial over another field, e.g. from Rat to BigRat
+    pub fn map<U: Field>(&self, f: impl Fn(&T) -> U) -> Poly<U> {
+        Poly {
+            terms: self
+                .terms
+                .iter()
+                .map(|term| Mono {
+                    val: f(&term.val),
+                    vars: term.vars.clone(),
+                })
+                .filter(|term| !term.val.is_zero())
+                .collect(),
+        }
+    }
+
     pub fn eval(&self, var: usize, val: T) -> Self {
         let mut new = Poly { terms: vec![] };
         let mut val_pow = T::one();
@@ -158,46 +234,30 @@ impl<T: Field> Poly<T> {
     }
 }
 
-impl Poly<Rat> {
-    pub fn norm(&self) -> Poly<Rat> {
-        let mut new = self.clone();
+impl<T: Fraction> Poly<T> {
+    // scaled to coprime integer coefs with a positive leading coef
+    pub fn norm(&self) -> Poly<T> {
+        let vals = self.terms.iter().map(|term| term.val.clone()).collect::<Vec<_>>();
+        let mut scale = T::primitive(&vals);
 
-        let mut all_terms_den = 1;
-        let mut all_terms_gcd = 1;
-
-        if let Some(t) = new.terms.last() {
-            all_terms_gcd = t.val.num;
-        }
-
-        for term in &new.terms {
-            all_terms_den *= term.val.den / gcd(all_terms_den, term.val.den);
+        if matches!(vals.last(), Some(lc) if *lc < T::zero()) {
+            scale = scale * -1;
         }
 
-        for term in &mut new.terms {
-            let term_gcd = gcd(term.val.num * all_terms_den, term.val.den);
-            term.val.num = term.val.num * all_terms_den / term_gcd;
-            term.val.den = 1;
-
-            all_terms_gcd = gcd(term.val.num, all_terms_gcd);
+        Poly {
+            terms: self
+                .terms
+                .iter()
+                .map(|term| Mono {
+                    val: term.val.clone() / scale.clone(),
+                    vars: term.vars.clone(),
+                })
+                .collect(),
         }
-
-        if let Some(t) = new.terms.last_mut() {
-            if t.val.num < 0 {
-                all_terms_gcd = -all_terms_gcd.abs();
-            } else {
-                all_terms_gcd = all_terms_gcd.abs();
-            }
-        }
-
-        for term in &mut new.terms {
-            term.val.num /= all_terms_gcd;
-        }
-
-        new
     }
 }
 
-impl Poly<Rat> {
+impl<T: Field> Poly<T> {
     pub fn format(&self, var_dict: &[String]) -> String {
         let mut s = String::new();
         if self.terms.is_empty() {
@@ -205,29 +265,23 @@ impl Poly<Rat> {
         }
 
         for (i, Mono { val, vars }) in (self.terms).iter().rev().enumerate() {
-            let coef: f64 = (*val).into();
-            if coef != 1. || vars.is_empty() {
-                if coef < 0. {
-                    if coef == -1. && !vars.is_empty() {
-                        if i == 0 {
-                            write!(s, "-").unwrap();
-                        } else {
-                            write!(s, " - ").unwrap();
-                        }
-                    } else if i == 0 {
-                        write!(s, "{coef}").unwrap();
-                    } else {
-                        write!(s, " - {}", -coef).unwrap();
-                    }
-                } else if i == 0 {
-                    write!(s, "{coef}").unwrap();
-                } else {
-                    write!(s, " + {coef}").unwrap();
+            let neg = *val < T::zero();
+            let abs = if neg { val.clone() * -1 } else { val.clone() };
+
+            if i == 0 {
+                if neg {
+                    write!(s, "-").unwrap();
                 }
-            } else if i != 0 {
+            } else if neg {
+                write!(s, " - ").unwrap();
+            } else {
                 write!(s, " + ").unwrap();
             }
 
+            if abs != T::one() || vars.is_empty() {
+                write!(s, "{}", abs.to_string()).unwrap();
+            }
+
             for (var, pow) in vars {
                 if *pow == 1 {
                     write!(s, "{}", var_dict[*var]).unwrap();
@@ -303,4 +357,22 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn parse() {
+        use crate::parse::ParseErrorKind;
+
+        let mut var_dict = vec!["y".to_string()];
+
+        let p = Poly::parse("3/4*x*(y - 2) - -y", &mut var_dict).unwrap();
+        assert_eq!(var_dict, vec!["y", "x"]);
+        assert_eq!("0.75yx + y - 1.5x", p.format(&var_dict));
+
+        let q = Poly::parse("(y + 1)^2 - y^2 - 2y", &mut var_dict).unwrap();
+        assert_eq!(Poly::constant(Rat::from(1)), q);
+
+        let err = Poly::parse("x +* y", &mut var_dict).unwrap_err();
+        assert_eq!(3, err.pos);
+        assert!(matches!(err.kind, ParseErrorKind::UnexpectedToken { .. }));
+    }
 }
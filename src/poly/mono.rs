@@ -14,8 +14,8 @@ impl<T: Field> Mono<T> {
             .iter()
             .find_map(|(v, pow)| match var.cmp(v) {
                 Ordering::Equal => Some(*pow as usize),
-                Ordering::Greater => Some(0),
-                Ordering::Less => None,
+                Ordering::Less => Some(0),
+                Ordering::Greater => None,
             })
             .unwrap_or(0)
     }
@@ -84,6 +84,83 @@ pub fn grevlex<T: Field>(lhs: &Mono<T>, rhs: &Mono<T>) -> Ordering {
     }
 }
 
+// term orders for leading terms and groebner bases; polys are always stored in grlex,
+// so the others are applied on top of that storage
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MonomialOrder {
+    Lex,
+    Grlex,
+    Grevlex,
+    // variables below the index against the rest, grevlex within each block;
+    // a basis in this order eliminates the first block
+    Block(usize),
+    // weighted total degree, missing weights being zero, then grevlex
+    Weight(Vec<u64>),
+}
+
+impl MonomialOrder {
+    pub fn cmp<T: Field>(&self, lhs: &Mono<T>, rhs: &Mono<T>) -> Ordering {
+        match self {
+            MonomialOrder::Lex => lex(&lhs.vars, &rhs.vars),
+            // the storage order; grevlex() breaks ties on the first variable
+            MonomialOrder::Grlex => grevlex(lhs, rhs),
+            MonomialOrder::Grevlex => revlex_graded(&lhs.vars, &rhs.vars),
+            MonomialOrder::Block(split) => {
+                let (lhs_hi, lhs_lo): (Vec<_>, Vec<_>) = lhs.vars.iter().partition(|(v, _)| v < split);
+                let (rhs_hi, rhs_lo): (Vec<_>, Vec<_>) = rhs.vars.iter().partition(|(v, _)| v < split);
+
+                revlex_graded(&lhs_hi, &rhs_hi).then_with(|| revlex_graded(&lhs_lo, &rhs_lo))
+            }
+            MonomialOrder::Weight(weights) => {
+                let weighted = |vars: &[(usize, u64)]| -> u64 {
+                    vars.iter().map(|(v, pow)| weights.get(*v).unwrap_or(&0) * pow).sum()
+                };
+
+                weighted(&lhs.vars)
+                    .cmp(&weighted(&rhs.vars))
+                    .then_with(|| revlex_graded(&lhs.vars, &rhs.vars))
+            }
+        }
+    }
+}
+
+// the larger exponent on the first variable where they differ
+fn lex(lhs: &[(usize, u64)], rhs: &[(usize, u64)]) -> Ordering {
+    for ((lhs_var, lhs_pow), (rhs_var, rhs_pow)) in lhs.iter().zip(rhs) {
+        match lhs_var.cmp(rhs_var) {
+            Ordering::Less => return Ordering::Greater,
+            Ordering::Greater => return Ordering::Less,
+            Ordering::Equal => match lhs_pow.cmp(rhs_pow) {
+                Ordering::Equal => continue,
+                ord => return ord,
+            },
+        }
+    }
+
+    lhs.len().cmp(&rhs.len())
+}
+
+// total degree, then the smaller exponent on the last variable where they differ
+fn revlex_graded(lhs: &[(usize, u64)], rhs: &[(usize, u64)]) -> Ordering {
+    let lhs_total_degree: u64 = lhs.iter().map(|(_, pow)| pow).sum();
+    let rhs_total_degree: u64 = rhs.iter().map(|(_, pow)| pow).sum();
+
+    lhs_total_degree.cmp(&rhs_total_degree).then_with(|| {
+        for ((lhs_var, lhs_pow), (rhs_var, rhs_pow)) in lhs.iter().rev().zip(rhs.iter().rev()) {
+            match lhs_var.cmp(rhs_var) {
+                Ordering::Less => return Ordering::Greater,
+                Ordering::Greater => return Ordering::Less,
+                Ordering::Equal => match lhs_pow.cmp(rhs_pow) {
+                    Ordering::Equal => continue,
+                    ord => return ord.reverse(),
+                },
+            }
+        }
+
+        Ordering::Equal
+    })
+}
+
 pub fn monomial_div<T: Field>(lhs: &Mono<T>, rhs: &Mono<T>) -> Option<Mono<T>> {
     if rhs.val.is_zero() {
         None
@@ -234,6 +311,20 @@ mod tests {
     use rand::prelude::*;
 
     #[test]
+    fn deg() {
+        let term = Mono {
+            val: Rat::from(1),
+            vars: vec![(0, 2), (2, 3)],
+        };
+
+        assert_eq!(2, term.deg(0));
+        assert_eq!(0, term.deg(1));
+        assert_eq!(3, term.deg(2));
+        assert_eq!(0, term.deg(3));
+    }
+
+    #[test]
+    #[allow(clippy::redundant_closure, clippy::needless_borrow)]
     fn ordering() {
         let var_dict = ["x".to_string(), "y".to_string(), "z".to_string()];
         let mut terms = vec![];
@@ -336,6 +427,36 @@ z
         }
     }
 
+    #[test]
+    fn orders() {
+        let mono = |vars: &[(usize, u64)]| Mono {
+            val: Rat::from(1),
+            vars: vars.to_vec(),
+        };
+
+        // x^2z, xy^2, y^3, x
+        let (x2z, xy2, y3, x) = (
+            mono(&[(0, 2), (2, 1)]),
+            mono(&[(0, 1), (1, 2)]),
+            mono(&[(1, 3)]),
+            mono(&[(0, 1)]),
+        );
+
+        assert_eq!(Ordering::Greater, MonomialOrder::Lex.cmp(&x2z, &xy2));
+        assert_eq!(Ordering::Greater, MonomialOrder::Lex.cmp(&x, &y3));
+        assert_eq!(Ordering::Greater, MonomialOrder::Grlex.cmp(&x2z, &xy2));
+        assert_eq!(Ordering::Less, MonomialOrder::Grlex.cmp(&x, &y3));
+        assert_eq!(Ordering::Less, MonomialOrder::Grevlex.cmp(&x2z, &xy2));
+        assert_eq!(Ordering::Greater, MonomialOrder::Grevlex.cmp(&xy2, &y3));
+
+        // x against {y, z}
+        assert_eq!(Ordering::Greater, MonomialOrder::Block(1).cmp(&x, &y3));
+        assert_eq!(Ordering::Less, MonomialOrder::Block(1).cmp(&xy2, &x2z));
+
+        assert_eq!(Ordering::Greater, MonomialOrder::Weight(vec![1, 2]).cmp(&y3, &x2z));
+        assert_eq!(Ordering::Greater, MonomialOrder::Weight(vec![3]).cmp(&x, &y3));
+    }
+
     #[test]
     fn div_mul_fuzz() {
         let mut rng = SmallRng::seed_from_u64(1);
@@ -4,6 +4,7 @@ use std::ops;
 
 use crate::poly::mono::*;
 use crate::poly::*;
+use crate::univariate::UPoly;
 
 impl<T: Field> ops::Add<Poly<T>> for Poly<T> {
     type Output = Self;
@@ -99,7 +100,16 @@ impl<T: Field> Poly<T> {
         new
     }
 
-    pub fn compound_divide(&self, divisors: &Vec<Poly<T>>) -> (Vec<Poly<T>>, Poly<T>) {
+    pub fn compound_divide(&self, divisors: &[Poly<T>]) -> (Vec<Poly<T>>, Poly<T>) {
+        self.compound_divide_in(divisors, &MonomialOrder::Grlex)
+    }
+
+    // division with leading terms taken under order
+    pub fn compound_divide_in(
+        &self,
+        divisors: &[Poly<T>],
+        order: &MonomialOrder,
+    ) -> (Vec<Poly<T>>, Poly<T>) {
         if divisors.is_empty() {
             return (vec![], self.clone());
         }
@@ -107,16 +117,14 @@ impl<T: Field> Poly<T> {
         let mut dividend = self.clone();
 
         let mut rem = Poly::constant(T::zero());
-        let mut quotients: Vec<VecDeque<Mono<T>>> = std::iter::repeat(VecDeque::from(vec![]))
-            .take(divisors.len())
-            .collect();
+        let mut quotients: Vec<VecDeque<Mono<T>>> = std::iter::repeat_n(VecDeque::from(vec![]), divisors.len()).collect();
 
         let mut curr_divisor = 0;
 
         while !dividend.is_zero() {
-            let self_lt = dividend.lt_mono();
+            let self_lt = dividend.lt_mono_in(order);
             if !divisors[curr_divisor].terms.is_empty() {
-                let div_lt = &divisors[curr_divisor].lt_mono();
+                let div_lt = &divisors[curr_divisor].lt_mono_in(order);
                 let self_over_div_lt = monomial_div(&self_lt, div_lt);
 
                 if let Some(self_over_div_lt) = self_over_div_lt {
@@ -136,12 +144,13 @@ impl<T: Field> Poly<T> {
             }
 
             if curr_divisor == divisors.len() {
+                let lt_index = dividend.terms.iter().rposition(|term| term.vars == self_lt.vars);
+                dividend.terms.remove(lt_index.unwrap());
+
                 let self_lt = Poly {
                     terms: vec![self_lt.clone()],
                 };
 
-                dividend.terms.pop();
-
                 rem = rem + self_lt;
                 curr_divisor = 0;
             }
@@ -158,7 +167,7 @@ impl<T: Field> Poly<T> {
     }
 
     pub fn try_divide(&self, divisor: &Poly<T>) -> Option<Poly<T>> {
-        let (quots, rem) = self.compound_divide(&vec![divisor.clone()]);
+        let (quots, rem) = self.compound_divide(std::slice::from_ref(divisor));
 
         if rem.is_zero() {
             Some(quots[0].clone())
@@ -196,9 +205,201 @@ impl<T: Field> Poly<T> {
     }
 }
 
+impl<T: Fraction> Poly<T> {
+    // greatest common divisor, normalized as by norm(); zero only when both are zero
+    pub fn gcd(&self, other: &Poly<T>) -> Poly<T> {
+        if self.is_zero() {
+            return other.norm();
+        }
+
+        if other.is_zero() {
+            return self.norm();
+        }
+
+        let mut vars = self
+            .terms
+            .iter()
+            .chain(&other.terms)
+            .flat_map(|term| term.vars.iter().map(|(var, _)| *var))
+            .collect::<Vec<_>>();
+        vars.sort();
+        vars.dedup();
+
+        match vars[..] {
+            [] => Poly::constant(T::one()),
+            [var] => UPoly::from_poly(self, var)
+                .gcd(&UPoly::from_poly(other, var))
+                .to_poly(var)
+                .norm(),
+            _ => {
+                let var = *vars
+                    .iter()
+                    .min_by_key(|var| (self.deg(**var).max(other.deg(**var)), **var))
+                    .unwrap();
+
+                self.gcd_by_images(other, var)
+            }
+        }
+    }
+
+    // brown's dense algorithm: interpolate the gcd in var from the gcds of images at var = 1, 2, ...,
+    // each scaled to the gcd of the leading coefficients, so no coefficient swell builds up
+    fn gcd_by_images(&self, other: &Poly<T>, var: usize) -> Poly<T> {
+        let (a_coefs, b_coefs) = (self.coefs_over(var), other.coefs_over(var));
+        let gcd_all = |coefs: &[(Mono<T>, UPoly<T>)]| {
+            coefs.iter().fold(UPoly(vec![]), |acc, (_, coef)| acc.gcd(coef))
+        };
+
+        let (a_content, b_content) = (gcd_all(&a_coefs), gcd_all(&b_coefs));
+        let content = a_content.gcd(&b_content).to_poly(var);
+
+        let a = self.try_divide(&a_content.to_poly(var)).unwrap();
+        let b = other.try_divide(&b_content.to_poly(var)).unwrap();
+        let a_lc = a_coefs.last().unwrap().1.div_rem(&a_content).0;
+        let b_lc = b_coefs.last().unwrap().1.div_rem(&b_content).0;
+        let lc = a_lc.gcd(&b_lc);
+
+        // the interpolant is lc / lc(gcd) * gcd, of no higher degree than this in var
+        let bound = lc.deg() + a.deg(var).min(b.deg(var));
+
+        // interpolant, product of (var - point) over the points used, leading term of the images
+        let mut interpolated: Option<(Poly<T>, UPoly<T>, Mono<T>)> = None;
+
+        for point in 1.. {
+            let point = T::from(point);
+
+            if a_lc.eval(&point).is_zero() || b_lc.eval(&point).is_zero() {
+                continue;
+            }
+
+            let image = a.eval(var, point.clone()).gcd(&b.eval(var, point.clone()));
+            let image = image.clone() * Poly::constant(lc.eval(&point) / image.lt_mono().val);
+            let image_lt = Mono { val: T::one(), ..image.lt_mono() };
+
+            if image_lt.vars.is_empty() {
+                return content.norm();
+            }
+
+            // a larger leading term means the point was unlucky, and a smaller one that all before it were
+            let stable = match &interpolated {
+                Some((_, _, lt)) if grevlex(&image_lt, lt) == Ordering::Greater => continue,
+                Some((poly, modulus, lt)) if grevlex(&image_lt, lt) == Ordering::Equal => {
+                    let residue = image - poly.eval(var, point.clone());
+                    let scale = Poly::constant(T::one() / modulus.eval(&point));
+                    let next = poly.clone() + residue * modulus.to_poly(var) * scale;
+                    let modulus = modulus.clone() * UPoly(vec![T::one(), T::zero() - point]);
+                    let stable = next == *poly;
+
+                    interpolated = Some((next, modulus, image_lt));
+                    stable
+                }
+                _ => {
+                    interpolated = Some((image, UPoly(vec![T::one(), T::zero() - point]), image_lt));
+                    false
+                }
+            };
+
+            let (poly, modulus, _) = interpolated.as_ref().unwrap();
+
+            if stable || modulus.deg() > bound {
+                let poly_content = gcd_all(&poly.coefs_over(var));
+                let candidate = poly.try_divide(&poly_content.to_poly(var)).unwrap();
+
+                if a.try_divide(&candidate).is_some() && b.try_divide(&candidate).is_some() {
+                    return (content * candidate).norm();
+                }
+
+                if modulus.deg() > bound {
+                    interpolated = None;
+                }
+            }
+        }
+
+        unreachable!()
+    }
+
+    // self in the variables other than var, with coefficients in var, by ascending monomial
+    fn coefs_over(&self, var: usize) -> Vec<(Mono<T>, UPoly<T>)> {
+        let mut coefs: Vec<(Mono<T>, UPoly<T>)> = vec![];
+
+        for term in &self.terms {
+            let (deg, mono) = term.coef(var);
+            let mono = Mono { val: T::one(), ..mono };
+            let mut coef = vec![T::zero(); deg + 1];
+            coef[0] = term.val.clone();
+
+            match coefs.iter_mut().find(|(key, _)| *key == mono) {
+                Some((_, acc)) => *acc = acc.clone() + UPoly(coef),
+                None => coefs.push((mono, UPoly(coef))),
+            }
+        }
+
+        coefs.sort_by(|(lhs, _), (rhs, _)| grevlex(lhs, rhs));
+
+        coefs
+    }
+
+    // gcd of the coefficients in var, a polynomial in the other variables
+    pub fn content(&self, var: usize) -> Poly<T> {
+        self.coefs(var)
+            .iter()
+            .fold(Poly::constant(T::zero()), |acc, c| acc.gcd(c))
+    }
+
+    // self divided by its content in var, normalized as by norm()
+    pub fn primitive_part(&self, var: usize) -> Poly<T> {
+        if self.is_zero() {
+            return self.clone();
+        }
+
+        self.try_divide(&self.content(var)).unwrap().norm()
+    }
+
+    // Yun's algorithm in var on the primitive part: pairwise coprime squarefree factors
+    // with their multiplicities, each normalized as by norm(), and the cofactor free of var
+    // that makes their product equal self
+    pub fn squarefree_decomposition(&self, var: usize) -> (Poly<T>, Vec<(Poly<T>, usize)>) {
+        if self.deg(var) == 0 {
+            return (self.clone(), vec![]);
+        }
+
+        let p = self.primitive_part(var);
+        let derivative = p.derivative(var);
+        let g = p.gcd(&derivative);
+
+        let mut w = p.try_divide(&g).unwrap();
+        let mut y = derivative.try_divide(&g).unwrap();
+        let mut factors = vec![];
+
+        for multiplicity in 1.. {
+            let z = y - w.derivative(var);
+            let factor = w.gcd(&z);
+
+            if factor.deg(var) > 0 {
+                factors.push((factor.clone(), multiplicity));
+            }
+
+            w = w.try_divide(&factor).unwrap();
+
+            if w.deg(var) == 0 {
+                break;
+            }
+
+            y = z.try_divide(&factor).unwrap();
+        }
+
+        let product = factors.iter().fold(Poly::constant(T::one()), |acc, (factor, multiplicity)| {
+            (0..*multiplicity).fold(acc, |acc, _| acc.mul_ref(factor))
+        });
+
+        (self.try_divide(&product).unwrap(), factors)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::Poly;
+    use crate::bigrat::BigRat;
     use crate::field::Zero;
     use crate::rational::Rat;
     use rand::prelude::*;
@@ -285,6 +486,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn gcd() {
+        let sys = crate::poly::system::System::parse(
+            "(x + y)*(x*y - z^2)*(2*z + 1), 3*(x + y)^2*(x - z)*(4*z + 2), \
+             x^2*y + x*y^2 + 2*x*y*z + y^2*z, x*y + y^2 + 2*x*z + 2*y*z",
+        )
+        .unwrap();
+        let format = |p: Poly<Rat>| p.format(&sys.var_dict);
+
+        assert_eq!("2xz + 2yz + x + y", format(sys.members[0].gcd(&sys.members[1])));
+        assert_eq!("1", format(sys.members[1].gcd(&Poly::var(1, 2))));
+        assert_eq!("x + y", format(sys.members[3].content(2)));
+        assert_eq!("x + y", format(sys.members[3].primitive_part(0)));
+        assert_eq!("y", format(sys.members[2].content(0)));
+        assert_eq!("x^2 + xy + 2xz + yz", format(sys.members[2].primitive_part(0)));
+
+        // remainder sequences swell past any practical size on this one, so the gcd is
+        // interpolated from univariate images instead
+        let sys = crate::poly::system::System::parse(
+            "(x*y + z^2 + 1)^2*(x - y*z + 3)*(x^2 + y^2*z - 2)",
+        )
+        .unwrap();
+        let p = sys.members[0].map(|c| BigRat::from(*c));
+
+        assert_eq!("xy + z^2 + 1", p.gcd(&p.derivative(0)).format(&sys.var_dict));
+    }
+
+    #[test]
+    fn squarefree_decomposition() {
+        let sys = crate::poly::system::System::parse("-3*y*(x - y)^3*(x^2 - 2*y)^2*(x + 1)").unwrap();
+        let (content, factors) = sys.members[0].squarefree_decomposition(0);
+
+        assert_eq!("-3y", content.format(&sys.var_dict));
+        assert_eq!(
+            vec![("x + 1".to_string(), 1), ("x^2 - 2y".to_string(), 2), ("x - y".to_string(), 3)],
+            factors
+                .iter()
+                .map(|(p, n)| (p.format(&sys.var_dict), *n))
+                .collect::<Vec<_>>()
+        );
+
+        let product = factors.iter().fold(content, |acc, (factor, multiplicity)| {
+            (0..*multiplicity).fold(acc, |acc, _| acc * factor.clone())
+        });
+        assert_eq!(sys.members[0], product);
+    }
+
     #[test]
     fn derivative() {
         let var_dict = vec!["x".to_string(), "y".to_string(), "z".to_string()];
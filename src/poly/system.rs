@@ -1,15 +1,25 @@
-use crate::poly::mono::{grevlex, monomial_div};
+use crate::algebraic::Algebraic;
+use crate::cad::lifting::Sample;
+use crate::univariate::UPoly;
+use crate::poly::mono::{monomial_div, monomial_lcm, monomial_mul, Mono, MonomialOrder};
+use crate::poly::f4;
+use crate::poly::hilbert::HilbertSeries;
 use crate::poly::Poly;
+use std::cmp::Ordering;
 use std::fmt;
 use std::rc::Rc;
 
 use super::Field;
-use crate::rational::Rat;
+use crate::field::Fraction;
+use crate::parse::{ParseError, Parser};
+use crate::rational::{exact, rounded, Inexact, Rat};
 
 #[derive(Clone)]
 pub struct System<T: Field> {
     pub var_dict: Rc<Vec<String>>,
     pub members: Vec<Poly<T>>,
+    // the term order gb() works in
+    pub order: MonomialOrder,
 }
 
 impl<T: Field> System<T> {
@@ -23,40 +33,98 @@ impl<T: Field> System<T> {
     pub fn get(&self, i: usize) -> Poly<T> {
         self.members[i].clone()
     }
+
+    pub fn with_order(&self, order: MonomialOrder) -> System<T> {
+        System {
+            order,
+            ..self.clone()
+        }
+    }
 }
 
 impl System<Rat> {
+    // comma separated polynomials; variables are indexed alphabetically, as in system!
+    pub fn parse(src: &str) -> Result<System<Rat>, ParseError> {
+        let mut parser = Parser::new(src, vec![])?;
+        let members = parser.poly_list()?;
+
+        let mut var_dict = parser.var_dict.clone();
+        var_dict.sort();
+
+        let index = parser
+            .var_dict
+            .iter()
+            .map(|v| var_dict.iter().position(|w| w == v).unwrap())
+            .collect::<Vec<_>>();
+
+        let members = members.iter().map(|p| reindex(p, &index)).collect();
+
+        Ok(System {
+            var_dict: Rc::new(var_dict),
+            members,
+            order: MonomialOrder::Grlex,
+        })
+    }
+
     pub fn constant(&self, val: i64) -> Poly<Rat> {
         Poly::constant(Rat::from(val))
     }
 
-    pub fn gb(&self) -> System<Rat> {
-        let mut sys = self.clone();
+    // fails instead of returning a basis computed with rounded arithmetic;
+    // stops early, since rounded pairs can keep buchberger from terminating
+    pub fn try_gb(&self) -> Result<System<Rat>, Inexact> {
+        exact(|| self.gb_while(Algorithm::Buchberger, || !rounded()))?.ok_or(Inexact)
+    }
+}
 
-        // buchberger
+impl<T: Fraction> System<T> {
+    pub fn gb(&self) -> System<T> {
+        self.gb_with(Algorithm::Buchberger)
+    }
 
-        let mut combs = {
-            let mut combs = vec![];
-            for i in 0..sys.members.len() {
-                for j in 0..sys.members.len() {
-                    if i != j {
-                        combs.push((sys.get(i), sys.get(j)));
-                    }
-                }
-            }
+    pub fn gb_with(&self, algorithm: Algorithm) -> System<T> {
+        self.gb_while(algorithm, || true).unwrap()
+    }
 
-            combs
+    // gives up, returning None, as soon as proceed() is false between pair reductions
+    fn gb_while(&self, algorithm: Algorithm, proceed: impl Fn() -> bool) -> Option<System<T>> {
+        let order = &self.order;
+        let mut sys = System {
+            members: vec![],
+            ..self.clone()
         };
 
-        while let Some((a, b)) = combs.pop() {
-            let s = Poly::s_poly(a, b);
-            let (_, rem) = s.compound_divide(&sys.members);
+        // pairs are pruned with the gebauer-moller criteria and taken in the normal strategy
+
+        let mut pairs = vec![];
+
+        for p in &self.members {
+            if !p.is_zero() {
+                add_member(&mut sys.members, &mut pairs, norm_in(p, order), order);
+            }
+        }
+
+        while !pairs.is_empty() {
+            if !proceed() {
+                return None;
+            }
+
+            if algorithm == Algorithm::F4 {
+                f4::step(&mut sys.members, &mut pairs, order);
+                continue;
+            }
+
+            // smallest lcm first
+            let next = (0..pairs.len())
+                .min_by(|&a, &b| order.cmp(&pairs[a].lcm, &pairs[b].lcm))
+                .unwrap();
+            let Pair { i, j, .. } = pairs.swap_remove(next);
+
+            let s = Poly::s_poly_in(sys.get(i), sys.get(j), order);
+            let (_, rem) = s.compound_divide_in(&sys.members, order);
 
             if !rem.is_zero() {
-                for member in &sys.members {
-                    combs.push((member.clone(), rem.clone()));
-                }
-                sys.members.push(rem);
+                add_member(&mut sys.members, &mut pairs, norm_in(&rem, order), order);
             }
         }
 
@@ -69,8 +137,8 @@ impl System<Rat> {
 
             for j in 0..sys.members.len() {
                 if i != j {
-                    let i_lt = sys.members[i].lt_mono();
-                    let j_lt = sys.members[j].lt_mono();
+                    let i_lt = sys.members[i].lt_mono_in(order);
+                    let j_lt = sys.members[j].lt_mono_in(order);
                     if let Some(m) = monomial_div(&i_lt, &j_lt) {
                         if m.vars.is_empty() {
                             divides_any = i > j;
@@ -93,28 +161,548 @@ impl System<Rat> {
         let mut keep2 = vec![];
 
         for (i, k) in keep.iter().enumerate() {
-            let (_, rem) = k.compound_divide(
+            let (_, rem) = k.compound_divide_in(
                 &keep
                     .iter()
                     .enumerate()
                     .filter_map(|(j, p)| if j != i { Some(p.clone()) } else { None })
-                    .collect(),
+                    .collect::<Vec<_>>(),
+                order,
             );
             keep2.push(rem);
         }
 
-        keep2.sort_by(|p, q| grevlex(&p.lt_mono(), &q.lt_mono()).reverse());
+        keep2.sort_by(|p, q| order.cmp(&p.lt_mono_in(order), &q.lt_mono_in(order)).reverse());
+
+        sys.members = keep2.iter().map(|p| norm_in(p, order)).collect();
+
+        Some(sys)
+    }
+
+    pub fn contains(&self, p: &Poly<T>) -> bool {
+        let gb = self.gb();
+        p.compound_divide_in(&gb.members, &gb.order).1.is_zero()
+    }
+
+    // p^k is in the ideal for some k exactly when 1 - tp and the ideal generate everything
+    pub fn radical_contains(&self, p: &Poly<T>) -> bool {
+        let (mut sys, t) = self.with_fresh_var();
+        sys.members.push(Poly::constant(T::one()) - Poly::var(t, 1) * p.clone());
+
+        sys.contains(&Poly::constant(T::one()))
+    }
+
+    // the same ideal with one more variable, last in the dict; returns its index
+    fn with_fresh_var(&self) -> (System<T>, usize) {
+        let t = self.var_dict.len();
+        let mut var_dict = (*self.var_dict).clone();
+        var_dict.push(format!("_t{t}"));
+
+        let sys = System {
+            var_dict: Rc::new(var_dict),
+            ..self.clone()
+        };
+
+        (sys, t)
+    }
+
+    // generators of the ideal's intersection with the ring in the other variables
+    fn eliminate_indices(&self, vars: &[usize]) -> Vec<Poly<T>> {
+        // weighting only the eliminated variables gives an elimination order
+        let mut weights = vec![0; self.var_dict.len()];
+        for var in vars {
+            weights[*var] = 1;
+        }
+
+        self.with_order(MonomialOrder::Weight(weights))
+            .gb()
+            .members
+            .into_iter()
+            .filter(|p| vars.iter().all(|var| p.deg(*var) == 0))
+            .collect()
+    }
+
+    // names outside var_dict are ignored, since the ideal does not involve them
+    pub fn eliminate(&self, vars: &[&str]) -> System<T> {
+        let vars = vars
+            .iter()
+            .filter_map(|var| self.var_dict.iter().position(|v| v == var))
+            .collect::<Vec<_>>();
+
+        System {
+            members: self.eliminate_indices(&vars),
+            ..self.clone()
+        }
+    }
+
+    // both systems over the variables of either, self's first and keeping their indices
+    fn merged(&self, other: &System<T>) -> (System<T>, System<T>) {
+        if self.var_dict == other.var_dict {
+            return (self.clone(), other.clone());
+        }
+
+        let mut var_dict = (*self.var_dict).clone();
+        let index = other
+            .var_dict
+            .iter()
+            .map(|var| match var_dict.iter().position(|v| v == var) {
+                Some(i) => i,
+                None => {
+                    var_dict.push(var.clone());
+                    var_dict.len() - 1
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let var_dict = Rc::new(var_dict);
+        let lhs = System {
+            var_dict: var_dict.clone(),
+            ..self.clone()
+        };
+        let rhs = System {
+            var_dict,
+            members: other.members.iter().map(|p| reindex(p, &index)).collect(),
+            order: other.order.clone(),
+        };
+
+        (lhs, rhs)
+    }
+
+    // t * self + (1 - t) * other, with t eliminated; over the variables of either system
+    pub fn intersect(&self, other: &System<T>) -> System<T> {
+        let (lhs, rhs) = self.merged(other);
+
+        let (mut sys, t) = lhs.with_fresh_var();
+        let t_poly = Poly::var(t, 1);
+        let one = Poly::constant(T::one());
+
+        sys.members = lhs
+            .members
+            .iter()
+            .map(|p| t_poly.clone() * p.clone())
+            .chain(rhs.members.iter().map(|p| (one.clone() - t_poly.clone()) * p.clone()))
+            .collect();
+
+        System {
+            members: sys.eliminate_indices(&[t]),
+            ..lhs
+        }
+    }
+
+    // polys whose product with p is in the ideal; everything when p is zero
+    pub fn quotient(&self, p: &Poly<T>) -> System<T> {
+        if p.is_zero() {
+            return System {
+                members: vec![Poly::constant(T::one())],
+                ..self.clone()
+            };
+        }
+
+        let principal = System {
+            members: vec![p.clone()],
+            ..self.clone()
+        };
+
+        System {
+            members: self
+                .intersect(&principal)
+                .members
+                .iter()
+                .map(|q| q.try_divide(p).unwrap())
+                .collect(),
+            ..self.clone()
+        }
+    }
+
+    // polys whose product with some power of p is in the ideal
+    pub fn saturate(&self, p: &Poly<T>) -> System<T> {
+        let (mut sys, t) = self.with_fresh_var();
+        sys.members.push(Poly::constant(T::one()) - Poly::var(t, 1) * p.clone());
+
+        System {
+            members: sys.eliminate_indices(&[t]),
+            ..self.clone()
+        }
+    }
+
+    // whether every member of self lies in other's ideal, over the variables of either
+    pub fn is_subideal(&self, other: &System<T>) -> bool {
+        let (lhs, rhs) = self.merged(other);
+        let gb = rhs.gb();
+
+        lhs.members
+            .iter()
+            .all(|p| p.compound_divide_in(&gb.members, &gb.order).1.is_zero())
+    }
+
+    pub fn ideal_eq(&self, other: &System<T>) -> bool {
+        self.is_subideal(other) && other.is_subideal(self)
+    }
+
+    // whether every variable has a pure power among the leading terms of a basis
+    fn finite_quotient(&self) -> bool {
+        self.is_unit() || (0..self.var_dict.len()).all(|var| {
+            self.members
+                .iter()
+                .any(|p| matches!(p.lt_mono_in(&self.order).vars[..], [(v, _)] if v == var))
+        })
+    }
+
+    // whether a basis generates everything, leaving no solutions at all
+    fn is_unit(&self) -> bool {
+        self.members.iter().any(|p| !p.is_zero() && p.terms.iter().all(|term| term.vars.is_empty()))
+    }
+
+    // every real solution, coordinates indexed like var_dict; None unless the system has
+    // finitely many complex solutions
+    pub fn real_solutions(&self) -> Option<Vec<Vec<Algebraic<T>>>> {
+        let lex = self.fglm(MonomialOrder::Lex)?;
+
+        if lex.is_unit() {
+            return Some(vec![]);
+        }
+
+        // the lex basis is triangular, so solve from the last variable back to the first
+        let mut samples = vec![Sample::origin()];
+
+        for var in (0..self.var_dict.len()).rev() {
+            let level = lex
+                .members
+                .iter()
+                .filter(|p| p.deg(var) > 0 && (0..var).all(|v| p.deg(v) == 0))
+                .collect::<Vec<_>>();
+
+            // some member leads with a pure power of var, so it never vanishes identically
+            let (pure, rest): (Vec<_>, Vec<_>) = level
+                .into_iter()
+                .partition(|p| matches!(p.lt_mono_in(&lex.order).vars[..], [(v, _)] if v == var));
+
+            samples = samples
+                .iter()
+                .flat_map(|sample| {
+                    // a linear member fixes var inside the sample's field, keeping prim small
+                    if let Some(next) = linear_section(sample, pure[0], var) {
+                        return vec![next];
+                    }
+
+                    let mut roots = sample.sections(pure[0], var);
+                    roots.sort();
+                    roots.dedup();
+
+                    roots
+                        .into_iter()
+                        .map(|root| sample.with_section(var, &root))
+                        .collect()
+                })
+                .filter(|next| {
+                    pure[1..]
+                        .iter()
+                        .chain(&rest)
+                        .all(|p| next.sign(p) == Ordering::Equal)
+                })
+                .collect();
+        }
+
+        Some(
+            samples
+                .into_iter()
+                .map(|sample| {
+                    let mut coords = sample.vars.into_iter().zip(sample.values).collect::<Vec<_>>();
+                    coords.sort_by_key(|(var, _)| *var);
+                    coords.into_iter().map(|(_, value)| value).collect()
+                })
+                .collect(),
+        )
+    }
+
+    // counts standard monomials of each degree, under self's order when it is graded
+    // and grevlex otherwise
+    pub fn hilbert_series(&self) -> HilbertSeries {
+        let gb = match self.order {
+            MonomialOrder::Grlex | MonomialOrder::Grevlex => self.gb(),
+            _ => self.with_order(MonomialOrder::Grevlex).gb(),
+        };
+
+        let vars = gb.var_dict.len();
+        let gens = gb
+            .members
+            .iter()
+            .filter(|p| !p.is_zero())
+            .map(|p| {
+                let mut exps = vec![0; vars];
+                for (var, pow) in p.lt_mono_in(&gb.order).vars {
+                    exps[var] = pow;
+                }
+                exps
+            })
+            .collect::<Vec<_>>();
+
+        HilbertSeries::of_monomials(&gens, vars)
+    }
+
+    pub fn hilbert_polynomial(&self) -> UPoly<Rat> {
+        self.hilbert_series().polynomial()
+    }
+
+    // krull dimension of the quotient ring; None for the unit ideal
+    pub fn dimension(&self) -> Option<usize> {
+        self.hilbert_series().dimension()
+    }
+
+    // monomials outside the leading term ideal in self's order, a basis of the quotient
+    // ring; None unless the system has finitely many complex solutions
+    pub fn standard_monomials(&self) -> Option<Vec<Mono<T>>> {
+        let gb = self.gb();
+
+        if !gb.finite_quotient() {
+            return None;
+        }
+
+        let leading = gb
+            .members
+            .iter()
+            .map(|p| p.lt_mono_in(&gb.order))
+            .collect::<Vec<_>>();
+
+        let monomial = |vars: Vec<(usize, u64)>| Mono {
+            val: T::one(),
+            vars,
+        };
+
+        let mut monos: Vec<Mono<T>> = vec![];
+        let mut candidates = vec![monomial(vec![])];
+
+        while let Some(m) = candidates.pop() {
+            if monos.contains(&m) || leading.iter().any(|lt| divides(lt, &m)) {
+                continue;
+            }
+
+            for var in 0..gb.var_dict.len() {
+                candidates.push(monomial_mul(&m, &monomial(vec![(var, 1)])));
+            }
+
+            monos.push(m);
+        }
+
+        monos.sort_by(|a, b| gb.order.cmp(a, b));
+
+        Some(monos)
+    }
+
+    // complex solutions counted with multiplicity
+    pub fn solution_count(&self) -> Option<usize> {
+        self.standard_monomials().map(|monos| monos.len())
+    }
+
+    // the reduced basis in target, found by linear algebra on the quotient ring of the
+    // basis in self's order; None unless the system has finitely many complex solutions
+    pub fn fglm(&self, target: MonomialOrder) -> Option<System<T>> {
+        let gb = self.gb();
+
+        if !gb.finite_quotient() {
+            return None;
+        }
+
+        let monomial = |vars: Vec<(usize, u64)>| Mono {
+            val: T::one(),
+            vars,
+        };
+        let normal_form = |m: &Mono<T>| {
+            Poly {
+                terms: vec![m.clone()],
+            }
+            .compound_divide_in(&gb.members, &gb.order)
+            .1
+        };
+        let coef = |p: &Poly<T>, vars: &[(usize, u64)]| {
+            p.terms
+                .iter()
+                .find(|term| term.vars == vars)
+                .map(|term| term.val.clone())
+        };
+
+        let mut rows: Vec<QuotientRow<T>> = vec![];
+        let mut members: Vec<Poly<T>> = vec![];
+        let mut candidates = vec![monomial(vec![])];
+        let mut seen = vec![];
+
+        while !candidates.is_empty() {
+            let next = (0..candidates.len())
+                .min_by(|&a, &b| target.cmp(&candidates[a], &candidates[b]))
+                .unwrap();
+            let m = candidates.swap_remove(next);
+
+            if seen.contains(&m.vars)
+                || members
+                    .iter()
+                    .any(|g| monomial_div(&m, &monomial(g.lt_mono_in(&target).vars)).is_some())
+            {
+                continue;
+            }
+
+            seen.push(m.vars.clone());
+
+            let mut vec = normal_form(&m);
+            let mut comb = Poly {
+                terms: vec![m.clone()],
+            };
+
+            for row in &rows {
+                if let Some(c) = coef(&vec, &row.pivot) {
+                    vec = vec - Poly::constant(c.clone()) * row.vec.clone();
+                    comb = comb - Poly::constant(c) * row.comb.clone();
+                }
+            }
+
+            if vec.is_zero() {
+                members.push(comb);
+            } else {
+                let pivot = vec.lt_mono();
+                let scale = Poly::constant(T::one() / pivot.val.clone());
+
+                rows.push(QuotientRow {
+                    pivot: pivot.vars,
+                    vec: scale.clone() * vec,
+                    comb: scale * comb,
+                });
+
+                for var in 0..gb.var_dict.len() {
+                    candidates.push(monomial_mul(&m, &monomial(vec![(var, 1)])));
+                }
+            }
+        }
+
+        members.sort_by(|p, q| target.cmp(&p.lt_mono_in(&target), &q.lt_mono_in(&target)).reverse());
+
+        Some(System {
+            var_dict: gb.var_dict.clone(),
+            members: members.iter().map(|p| norm_in(p, &target)).collect(),
+            order: target,
+        })
+    }
+}
+
+// buchberger reduces one s-polynomial at a time; f4 reduces every pair of the lowest
+// degree together as rows of one matrix
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Algorithm {
+    Buchberger,
+    F4,
+}
 
-        sys.members = keep2.iter().map(|p| p.norm()).collect();
+pub(super) struct Pair<T: Field> {
+    pub i: usize,
+    pub j: usize,
+    pub lcm: Mono<T>,
+}
 
-        sys
+// the one solution of p for var over sample, when p is linear in var with a leading
+// coefficient invertible in the sample's field
+fn linear_section<T: Fraction>(sample: &Sample<T>, p: &Poly<T>, var: usize) -> Option<Sample<T>> {
+    if p.deg(var) != 1 {
+        return None;
     }
+
+    let coefs = p.coefs(var);
+    let inv = sample.substitute(&coefs[0]).inverse_mod(&sample.prim.p)?;
+    let coord = UPoly(vec![T::from(-1)]) * sample.substitute(&coefs[1]) * inv;
+
+    Some(sample.with_coord(var, coord))
 }
 
-impl fmt::Debug for System<Rat> {
+// an echelon row of normal forms in fglm, with comb the combination of staircase
+// monomials whose normal form it is
+struct QuotientRow<T: Field> {
+    pivot: Vec<(usize, u64)>,
+    vec: Poly<T>,
+    comb: Poly<T>,
+}
+
+fn coprime<T: Field>(a: &Mono<T>, b: &Mono<T>) -> bool {
+    a.vars.iter().all(|(v, _)| b.deg(*v) == 0)
+}
+
+fn divides<T: Field>(a: &Mono<T>, b: &Mono<T>) -> bool {
+    monomial_div(b, a).is_some()
+}
+
+// p with variable var renamed to index[var]; renaming changes the term order, so each
+// term is rebuilt
+fn reindex<T: Field>(p: &Poly<T>, index: &[usize]) -> Poly<T> {
+    p.terms.iter().fold(Poly::constant(T::zero()), |acc, term| {
+        acc + term.vars.iter().fold(Poly::constant(term.val.clone()), |acc, (var, pow)| {
+            acc * Poly::var(index[*var], *pow)
+        })
+    })
+}
+
+// primitive, with a positive leading coefficient under order
+pub(super) fn norm_in<T: Fraction>(p: &Poly<T>, order: &MonomialOrder) -> Poly<T> {
+    let p = p.norm();
+
+    if p.lt_mono_in(order).val < T::zero() {
+        p.map(|val| val.clone() * -1)
+    } else {
+        p
+    }
+}
+
+// push h onto the basis, pruning the pair queue as it grows
+pub(super) fn add_member<T: Field>(
+    members: &mut Vec<Poly<T>>,
+    pairs: &mut Vec<Pair<T>>,
+    h: Poly<T>,
+    order: &MonomialOrder,
+) {
+    let k = members.len();
+    let h_lt = h.lt_mono_in(order);
+    let lcm_with = |i: usize| monomial_lcm(members[i].lt_mono_in(order), h_lt.clone());
+
+    // chain criterion: (i, j) is redundant given (i, k) and (j, k) when lt(h) divides
+    // their lcm without matching either of the new lcms
+    pairs.retain(|pair| {
+        !divides(&h_lt, &pair.lcm) || lcm_with(pair.i) == pair.lcm || lcm_with(pair.j) == pair.lcm
+    });
+
+    let mut new_pairs = (0..k)
+        .map(|i| Pair { i, j: k, lcm: lcm_with(i) })
+        .collect::<Vec<_>>();
+
+    // drop pairs whose lcm is a proper multiple of another new lcm
+    let lcms = new_pairs.iter().map(|pair| pair.lcm.clone()).collect::<Vec<_>>();
+    new_pairs.retain(|pair| {
+        !lcms.iter().any(|lcm| *lcm != pair.lcm && divides(lcm, &pair.lcm))
+    });
+
+    // of pairs sharing an lcm keep one, and none if any of them is coprime,
+    // since s-polys of coprime leading terms always reduce to zero
+    let mut kept: Vec<Pair<T>> = vec![];
+    let mut coprime_lcms: Vec<Mono<T>> = vec![];
+
+    for pair in new_pairs {
+        if coprime(&members[pair.i].lt_mono_in(order), &h_lt) {
+            coprime_lcms.push(pair.lcm.clone());
+        }
+
+        if !kept.iter().any(|other| other.lcm == pair.lcm) {
+            kept.push(pair);
+        }
+    }
+
+    kept.retain(|pair| !coprime_lcms.contains(&pair.lcm));
+
+    pairs.extend(kept);
+    members.push(h);
+}
+
+impl<T: Field> fmt::Debug for System<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "[")?;
         for (i, p) in self.members.iter().enumerate() {
+            // leading term first in the system's order
+            let mut p = p.clone();
+            p.terms.sort_by(|a, b| self.order.cmp(a, b));
+
             write!(f, "{}", p.format(&self.var_dict))?;
             if i + 1 < self.members.len() {
                 write!(f, ", ")?;
@@ -140,4 +728,334 @@ mod tests {
             format!("{:?}", sys.gb())
         );
     }
+
+    #[test]
+    fn gb_criteria() {
+        use super::System;
+        use crate::poly::Poly;
+
+        // cyclic 4
+        let sys = System::parse(
+            "a + b + c + d, a*b + b*c + c*d + d*a, a*b*c + b*c*d + c*d*a + d*a*b, a*b*c*d - 1",
+        )
+        .unwrap();
+        let gb = sys.gb();
+
+        assert_eq!(7, gb.members.len());
+
+        for p in &sys.members {
+            assert!(p.compound_divide(&gb.members).1.is_zero());
+        }
+
+        for a in &gb.members {
+            for b in &gb.members {
+                let s = Poly::s_poly(a.clone(), b.clone());
+                assert!(s.compound_divide(&gb.members).1.is_zero());
+            }
+        }
+    }
+
+    #[test]
+    fn f4() {
+        use super::{Algorithm, System};
+
+        for src in [
+            "x + y^2 + z, x - y + 3z + 5, x - 2y + 3",
+            "x^2*y + 1, 2x + y*z - 1, x - y^2*z^2 + 1",
+            "a + b + c + d, a*b + b*c + c*d + d*a, a*b*c + b*c*d + c*d*a + d*a*b, a*b*c*d - 1",
+            "x1 + 2x2 + 2x3 + 2x4 - 1, x1^2 + 2x2^2 + 2x3^2 + 2x4^2 - x1, 2x1*x2 + 2x2*x3 + 2x3*x4 - x2, x2^2 + 2x1*x3 + 2x2*x4 - x3",
+            "u*v - w, v*w - x, w*x - y, x*y - z, y*z - u, z*u - 1",
+        ] {
+            let sys = System::parse(src).unwrap();
+
+            assert_eq!(
+                format!("{:?}", sys.gb()),
+                format!("{:?}", sys.gb_with(Algorithm::F4))
+            );
+        }
+    }
+
+    #[test]
+    fn orders() {
+        use super::{Algorithm, System};
+        use crate::poly::mono::MonomialOrder;
+
+        let sys = System::parse("x + y^2 + z, x - y + 3z + 5, x - 2y + 3").unwrap();
+
+        // lex gives a triangular basis
+        let lex = sys.with_order(MonomialOrder::Lex);
+        assert_eq!("[x + 6z + 7, y + 3z + 2, 9z^2 + 7z - 3]", format!("{:?}", lex.gb()));
+        assert_eq!(format!("{:?}", lex.gb()), format!("{:?}", lex.gb_with(Algorithm::F4)));
+
+        let weighted = sys.with_order(MonomialOrder::Weight(vec![1, 2, 3]));
+        assert_eq!("[6z + x + 7, 3x^2 + 28x + 13, 2y - x - 3]", format!("{:?}", weighted.gb()));
+
+        let sys = System::parse("x^2 + y^2 + z^2 - 1, x*y - z, x - y*z").unwrap();
+        let grevlex = sys.with_order(MonomialOrder::Grevlex);
+        assert_eq!(
+            "[xz^2, z^3, x^2 - z^2, xy - z, y^2 + 2z^2 - 1, yz - x]",
+            format!("{:?}", grevlex.gb())
+        );
+        assert_eq!(format!("{:?}", grevlex.gb()), format!("{:?}", grevlex.gb_with(Algorithm::F4)));
+
+        // eliminating t from x = t^2, y = t^3 leaves the cusp
+        let sys = System::parse("x - t^2, y - t^3").unwrap();
+        let gb = sys.with_order(MonomialOrder::Block(1)).gb();
+        assert_eq!("[t^2 - x, tx - y, ty - x^2, x^3 - y^2]", format!("{:?}", gb));
+    }
+
+    #[test]
+    fn fglm() {
+        use super::System;
+        use crate::poly::mono::MonomialOrder;
+
+        for src in [
+            "x + y^2 + z, x - y + 3z + 5, x - 2y + 3",
+            "x^2*y + 1, 2x + y*z - 1, x - y^2*z^2 + 1",
+            "x^2 + y^2 + z^2 - 1, x*y - z, x - y*z",
+            "x1 + 2x2 + 2x3 - 1, x1^2 + 2x2^2 + 2x3^2 - x1, 2x1*x2 + 2x2*x3 - x2",
+        ] {
+            let sys = System::parse(src).unwrap();
+            let lex = sys.fglm(MonomialOrder::Lex);
+
+            assert_eq!(
+                format!("{:?}", sys.with_order(MonomialOrder::Lex).gb()),
+                format!("{:?}", lex.unwrap())
+            );
+        }
+
+        let sys = System::parse("x^2 + y^2 - 4, x*y - 1").unwrap();
+        let lex = sys.with_order(MonomialOrder::Grevlex).fglm(MonomialOrder::Lex).unwrap();
+        assert_eq!("[x + y^3 - 4y, y^4 - 4y^2 + 1]", format!("{:?}", lex));
+
+        // a curve has infinitely many solutions
+        assert!(System::parse("x*y - 1").unwrap().fglm(MonomialOrder::Lex).is_none());
+
+        // the unit ideal has none, and an empty quotient
+        let unit = System::parse("x*y - 1, x").unwrap().fglm(MonomialOrder::Lex);
+        assert_eq!("[1]", format!("{:?}", unit.unwrap()));
+    }
+
+    #[test]
+    fn membership() {
+        use super::System;
+        use crate::poly::Poly;
+
+        let sys = System::parse("x^2 - y, y^2 - 1").unwrap();
+        let mut var_dict = (*sys.var_dict).clone();
+
+        assert!(sys.contains(&Poly::parse("x^4 - 1", &mut var_dict).unwrap()));
+        assert!(sys.contains(&Poly::parse("x^2*y - 1", &mut var_dict).unwrap()));
+        assert!(!sys.contains(&Poly::parse("x - 1", &mut var_dict).unwrap()));
+
+        // x is in the radical of x^2 but not in the ideal
+        let sys = System::parse("x^2, y - 1").unwrap();
+        let x = Poly::parse("x", &mut var_dict).unwrap();
+        assert!(!sys.contains(&x));
+        assert!(sys.radical_contains(&x));
+        assert!(!sys.radical_contains(&Poly::parse("y", &mut var_dict).unwrap()));
+
+        let a = System::parse("x^2 - y, y^2 - 1").unwrap();
+        let b = System::parse("x^2 - y, x^4 - 1").unwrap();
+        let c = System::parse("x^2 - y, x^4 - 1, x - y").unwrap();
+
+        assert!(a.ideal_eq(&b));
+        assert!(a.is_subideal(&c));
+        assert!(!c.is_subideal(&a));
+        assert!(!a.ideal_eq(&c));
+
+        // systems built separately need not share a variable dict
+        let x_minus_y = System::parse("x - y").unwrap();
+        let y = System::parse("y").unwrap();
+        assert!(!x_minus_y.is_subideal(&y));
+        assert!(y.is_subideal(&System::parse("x - y, x").unwrap()));
+        assert!(!y.ideal_eq(&x_minus_y));
+    }
+
+    #[test]
+    fn elimination() {
+        use super::System;
+        use crate::poly::Poly;
+        use crate::rational::Rat;
+
+        let sys = System::parse("x - t^2, y - t^3").unwrap();
+        assert_eq!("[x^3 - y^2]", format!("{:?}", sys.eliminate(&["t"])));
+        assert_eq!("[x^3 - y^2]", format!("{:?}", sys.eliminate(&["t", "w"])));
+
+        let sys = System::parse("x + y^2 + z, x - y + 3z + 5, x - 2y + 3").unwrap();
+        assert_eq!("[9z^2 + 7z - 3]", format!("{:?}", sys.eliminate(&["x", "y"])));
+
+        let a = System::parse("x*y, y^2").unwrap();
+        let b = System {
+            members: vec![a.var("x", 2)],
+            ..a.clone()
+        };
+        assert_eq!("[x^2y]", format!("{:?}", a.intersect(&b)));
+
+        let x_only = System::parse("x").unwrap();
+        assert_eq!("[xy]", format!("{:?}", x_only.intersect(&System::parse("y").unwrap())));
+
+        let (x, y) = (a.var("x", 1), a.var("y", 1));
+
+        let sys = System::parse("x^2*y, x*y^2").unwrap();
+        assert!(sys.quotient(&x).ideal_eq(&a));
+        assert!(sys.quotient(&Poly::constant(Rat::from(0))).contains(&Poly::constant(Rat::from(1))));
+
+        // saturating by x drops the component on x = 0
+        let sys = System::parse("x*y - x").unwrap();
+        assert_eq!("[y - 1]", format!("{:?}", sys.saturate(&x)));
+
+        let sys = System::parse("x*y - x, x^2").unwrap();
+        assert!(sys.saturate(&x).contains(&Poly::constant(Rat::from(1))));
+        assert!(sys.saturate(&y).ideal_eq(&sys));
+    }
+
+    #[test]
+    fn real_solutions() {
+        use super::System;
+        use crate::rational::Rat;
+        use crate::univariate::UPoly;
+        use std::cmp::Ordering;
+
+        // the hyperbola xy = 1 meets the circle of radius 2 four times
+        let sys = System::parse("x^2 + y^2 - 4, x*y - 1").unwrap();
+        let solutions = sys.real_solutions().unwrap();
+
+        assert_eq!(4, solutions.len());
+
+        for solution in &solutions {
+            let (x, y) = (&solution[0], &solution[1]);
+
+            assert_eq!(Some(x.clone()), y.inv());
+            assert_eq!(Ordering::Equal, y.sign_at(&UPoly(vec![1, 0, -4, 0, 1].into_iter().map(Rat::from).collect())));
+        }
+
+        let sys = System::parse("x + y^2 + z, x - y + 3z + 5, x - 2y + 3").unwrap();
+        let solutions = sys.real_solutions().unwrap();
+
+        assert_eq!(2, solutions.len());
+        assert_eq!(Ordering::Less, solutions[0][2].compare(&solutions[1][2]));
+
+        assert!(System::parse("x^2 + 1, y").unwrap().real_solutions().unwrap().is_empty());
+        assert!(System::parse("x - 1, x - 2").unwrap().real_solutions().unwrap().is_empty());
+        assert!(System::parse("x*y - 1").unwrap().real_solutions().is_none());
+    }
+
+    #[test]
+    fn hilbert() {
+        use super::System;
+        use crate::poly::mono::MonomialOrder;
+        use crate::rational::Rat;
+        use crate::univariate::UPoly;
+
+        // four complex solutions, all of them real
+        let sys = System::parse("x^2 + y^2 - 4, x*y - 1").unwrap();
+        assert_eq!(Some(0), sys.dimension());
+        assert_eq!(Some(4), sys.solution_count());
+        assert!(sys.hilbert_polynomial().is_zero());
+        assert_eq!(
+            vec![vec![], vec![(1, 1)], vec![(0, 1)], vec![(1, 2)]],
+            sys.standard_monomials().unwrap().into_iter().map(|m| m.vars).collect::<Vec<_>>()
+        );
+
+        // a double root at the origin counts twice, whatever the order
+        let sys = System::parse("x^2, y - x").unwrap();
+        assert_eq!(Some(2), sys.solution_count());
+        assert_eq!(Some(2), sys.with_order(MonomialOrder::Lex).solution_count());
+
+        // a plane and a line
+        let sys = System::parse("x*z, y*z").unwrap();
+        assert_eq!(Some(2), sys.dimension());
+        assert_eq!(UPoly(vec![Rat::from(1), Rat::from(2)]), sys.hilbert_polynomial());
+        assert_eq!(None, sys.solution_count());
+
+        let sys = System::parse("x*y - 1").unwrap();
+        assert_eq!(Some(1), sys.with_order(MonomialOrder::Lex).dimension());
+        assert_eq!(UPoly(vec![Rat::from(2)]), sys.hilbert_polynomial());
+
+        assert_eq!(None, System::parse("x - 1, x - 2").unwrap().dimension());
+    }
+
+    #[test]
+    fn parse() {
+        use super::System;
+        use crate::parse::ParseErrorKind;
+
+        let sys = System::parse("z*(x - 3/4) + y^2, -(x + y)^2, 2x - 1/2,").unwrap();
+
+        assert_eq!(*sys.var_dict, vec!["x", "y", "z"]);
+        assert_eq!(
+            "[xz + y^2 - 0.75z, -x^2 - 2xy - y^2, 2x - 0.5]",
+            format!("{:?}", sys)
+        );
+
+        // same polynomials as the macro builds
+        let expected = crate::system! { x^2*y + 1, 2*x + y*z - 1 };
+        let parsed = System::parse("x^2*y + 1, 2*x + y*z - 1").unwrap();
+        assert_eq!(expected.members, parsed.members);
+
+        assert_eq!(0, System::parse("").unwrap().members.len());
+        assert_eq!(
+            ParseErrorKind::UnexpectedToken {
+                found: "=".to_string(),
+                expected: "end of input"
+            },
+            System::parse("x, y = 1").unwrap_err().kind
+        );
+    }
+
+    #[test]
+    fn exact_gb() {
+        use super::System;
+        use crate::bigrat::BigRat;
+        use crate::poly::Poly;
+
+        let sys = System::parse("x^3 - 123456789y, y^3 - 987654321x + 1").unwrap();
+        let big = System {
+            var_dict: sys.var_dict.clone(),
+            members: sys.members.iter().map(|p| p.map(|c| BigRat::from(*c))).collect(),
+            order: sys.order.clone(),
+        };
+
+        let gb = big.gb();
+
+        // the inputs are in the ideal and every s-polynomial reduces to zero
+        for p in &big.members {
+            assert!(p.compound_divide(&gb.members).1.is_zero());
+        }
+
+        for p in &gb.members {
+            for q in &gb.members {
+                if p != q {
+                    let s = Poly::s_poly(p.clone(), q.clone());
+                    assert!(s.compound_divide(&gb.members).1.is_zero());
+                }
+            }
+        }
+
+        let small = crate::system! { x^2*y + 1, 2*x + y*z - 1, x - y^2*z^2 + 1 };
+        let big = System {
+            var_dict: small.var_dict.clone(),
+            members: small.members.iter().map(|p| p.map(|c| BigRat::from(*c))).collect(),
+            order: small.order.clone(),
+        };
+
+        assert_eq!("[4x - 5, 25y + 16, 32z - 75]", format!("{:?}", big.gb()));
+    }
+
+    #[test]
+    fn inexact_gb() {
+        use super::System;
+
+        let sys = crate::system! { x^2*y + 1, 2*x + y*z - 1, x - y^2*z^2 + 1 };
+        assert_eq!("[4x - 5, 25y + 16, 32z - 75]", format!("{:?}", sys.try_gb().unwrap()));
+
+        // the basis contains 2y^2 - a^2 with a^2 past i64
+        let sys = System::parse("x*y - 5000000000, x^2 - 2").unwrap();
+        assert!(sys.try_gb().is_err());
+
+        // the common denominator of the first member is past i64
+        let sys = System::parse("x/1000003 + y/1000033 + z/1000037 + w/1000039 + v, x - 1").unwrap();
+        assert!(sys.try_gb().is_err());
+    }
 }
@@ -1,10 +1,44 @@
 use std::{
+    cell::Cell,
     cmp::{Ord, Ordering, PartialOrd},
-    ops,
+    fmt, ops,
 };
 
 use crate::field;
 
+thread_local! {
+    // set whenever an operation drops low bits to avoid overflow
+    static INEXACT: Cell<bool> = const { Cell::new(false) };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Inexact;
+
+impl fmt::Display for Inexact {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "rational arithmetic overflowed and was rounded")
+    }
+}
+
+// whether rounding has happened on this thread since the innermost exact() began
+pub fn rounded() -> bool {
+    INEXACT.get()
+}
+
+// runs f, failing if any Rat operation in it on this thread had to round
+pub fn exact<R>(f: impl FnOnce() -> R) -> Result<R, Inexact> {
+    let outer = INEXACT.replace(false);
+    let result = f();
+    let inexact = INEXACT.replace(outer);
+
+    if inexact {
+        INEXACT.set(true);
+        Err(Inexact)
+    } else {
+        Ok(result)
+    }
+}
+
 // overflow-safe 127 bit rational type
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Rat {
@@ -28,60 +62,119 @@ impl Rat {
     pub fn is_zero(&self) -> bool {
         self.num == 0
     }
+
+    pub fn checked_add(self, rhs: Rat) -> Option<Rat> {
+        Rat::checked(|| self + rhs)
+    }
+
+    pub fn checked_sub(self, rhs: Rat) -> Option<Rat> {
+        Rat::checked(|| self - rhs)
+    }
+
+    pub fn checked_mul(self, rhs: Rat) -> Option<Rat> {
+        Rat::checked(|| self * rhs)
+    }
+
+    pub fn checked_div(self, rhs: Rat) -> Option<Rat> {
+        Rat::checked(|| self / rhs)
+    }
+
+    // unlike exact(), a rounding reported as None is handled and not passed to the caller's flag
+    fn checked(op: impl FnOnce() -> Rat) -> Option<Rat> {
+        let outer = INEXACT.replace(false);
+        let result = op();
+        let inexact = INEXACT.replace(outer);
+
+        if inexact {
+            None
+        } else {
+            Some(result)
+        }
+    }
+
+    // canonical form: positive denominator, no common factor, zero as 0/1
+    fn reduced(mut num: i64, mut den: i64) -> Rat {
+        if num == 0 {
+            return Rat { num: 0, den: 1 };
+        }
+
+        // neither gcd nor negation is safe on i64::MIN
+        if num == i64::MIN || den == i64::MIN {
+            return Rat::fit(num as i128, den as i128);
+        }
+
+        let g = gcd(num, den).abs();
+        num /= g;
+        den /= g;
+
+        if den < 0 {
+            num = -num;
+            den = -den;
+        }
+
+        Rat { num, den }
+    }
+
+    // the exact value num / den, canonical; when it does not fit, both parts drop their low
+    // bits, or the value saturates at i64::MAX in magnitude, and the rounding is flagged
+    fn fit(mut num: i128, mut den: i128) -> Rat {
+        if den < 0 {
+            num = -num;
+            den = -den;
+        }
+
+        if num == 0 {
+            return Rat { num: 0, den: 1 };
+        }
+
+        let g = gcd_wide(num.unsigned_abs(), den as u128) as i128;
+        let (sign, mag, den) = (num.signum(), num.abs() / g, den / g);
+        let max = i64::MAX as i128;
+
+        if mag <= max && den <= max {
+            return Rat {
+                num: (sign * mag) as i64,
+                den: den as i64,
+            };
+        }
+
+        INEXACT.set(true);
+
+        if mag / den > max {
+            return Rat {
+                num: sign as i64 * i64::MAX,
+                den: 1,
+            };
+        }
+
+        // a denominator below 2^shift leaves only the integer part
+        let shift = 65 - mag.max(den).leading_zeros();
+        if den >> shift == 0 {
+            return Rat::from((sign * (mag / den)) as i64);
+        }
+
+        Rat::reduced((sign * (mag >> shift)) as i64, (den >> shift) as i64)
+    }
 }
 
 impl PartialOrd<Rat> for Rat {
-    // Required method
     fn partial_cmp(&self, other: &Rat) -> Option<Ordering> {
-        if self == other {
-            Some(Ordering::Equal)
-        } else if (self.num < 0 && other.num >= 0) || (self.num == 0 && other.num > 0) {
-            Some(Ordering::Less)
-        } else if (self.num > 0 && other.num <= 0) || (self.num == 0 && other.num < 0) {
-            Some(Ordering::Greater)
-        } else {
-            let mut self_clone = *self;
-            let mut other_clone = *other;
-            loop {
-                let lhs = match self_clone.num.checked_mul(other_clone.den) {
-                    Some(v) => v,
-                    None => {
-                        if self_clone.num > other_clone.den {
-                            self_clone.num >>= 1;
-                            self_clone.den >>= 1;
-                        } else {
-                            other_clone.num >>= 1;
-                            other_clone.den >>= 1;
-                        }
-
-                        continue;
-                    }
-                };
-
-                let rhs = match self_clone.den.checked_mul(other_clone.num) {
-                    Some(v) => v,
-                    None => {
-                        if self_clone.den > other_clone.num {
-                            self_clone.num >>= 1;
-                            self_clone.den >>= 1;
-                        } else {
-                            other_clone.num >>= 1;
-                            other_clone.den >>= 1;
-                        }
-
-                        continue;
-                    }
-                };
-
-                return Some(lhs.cmp(&rhs));
-            }
-        }
+        Some(self.cmp(other))
     }
 }
 
 impl Ord for Rat {
     fn cmp(&self, other: &Rat) -> Ordering {
-        self.partial_cmp(other).unwrap()
+        if self == other {
+            Ordering::Equal
+        } else if (self.num < 0 && other.num >= 0) || (self.num == 0 && other.num > 0) {
+            Ordering::Less
+        } else if (self.num > 0 && other.num <= 0) || (self.num == 0 && other.num < 0) {
+            Ordering::Greater
+        } else {
+            // cross multiplication is exact in 128 bits
+            (self.num as i128 * other.den as i128).cmp(&(other.num as i128 * self.den as i128))
+        }
     }
 }
 
@@ -128,90 +221,23 @@ impl field::One for Rat {
 impl ops::Add<Rat> for Rat {
     type Output = Self;
 
-    fn add(mut self, mut rhs: Self) -> Self {
-        loop {
-            let den_gcd = gcd(self.den, rhs.den);
-
-            let lhs_num = match (rhs.den / den_gcd).checked_mul(self.num) {
-                Some(v) => v,
-                None => {
-                    if self.num == i64::MIN
-                        || (rhs.den != i64::MIN && self.num.abs() > rhs.den.abs())
-                    {
-                        self.num >>= 1;
-                        self.den >>= 1;
-                    } else {
-                        rhs.num >>= 1;
-                        rhs.den >>= 1;
-                    }
-                    continue;
-                }
-            };
-
-            let rhs_num = match (self.den / den_gcd).checked_mul(rhs.num) {
-                Some(v) => v,
-                None => {
-                    if self.den == i64::MIN
-                        || (rhs.num != i64::MIN && self.den.abs() > rhs.num.abs())
-                    {
-                        self.num >>= 1;
-                        self.den >>= 1;
-                    } else {
-                        rhs.num >>= 1;
-                        rhs.den >>= 1;
-                    }
-
-                    continue;
-                }
-            };
-
-            let num = match lhs_num.checked_add(rhs_num) {
-                Some(v) => v,
-                None => {
-                    if self.num == i64::MIN
-                        || (rhs.num != i64::MIN && self.num.abs() > rhs.num.abs())
-                    {
-                        self.num >>= 1;
-                        self.den >>= 1;
-                    } else {
-                        rhs.num >>= 1;
-                        rhs.den >>= 1;
-                    }
-
-                    continue;
-                }
-            };
-
-            let den = match (self.den / den_gcd).checked_mul(rhs.den) {
-                Some(v) => v,
-                None => {
-                    if self.den == i64::MIN
-                        || (rhs.den != i64::MIN && self.den.abs() > rhs.den.abs())
-                    {
-                        self.num >>= 1;
-                        self.den >>= 1;
-                    } else {
-                        rhs.num >>= 1;
-                        rhs.den >>= 1;
-                    }
-
-                    continue;
-                }
-            };
-
-            let new_gcd = gcd(num, den).abs();
-
-            if den > 0 {
-                return Self {
-                    num: num / new_gcd,
-                    den: den / new_gcd,
-                };
-            } else {
-                return Self {
-                    num: -num / new_gcd,
-                    den: -den / new_gcd,
-                };
-            }
+    fn add(self, rhs: Self) -> Self {
+        let den_gcd = gcd(self.den, rhs.den);
+
+        let lhs_num = (rhs.den / den_gcd).checked_mul(self.num);
+        let rhs_num = (self.den / den_gcd).checked_mul(rhs.num);
+        let num = lhs_num
+            .zip(rhs_num)
+            .and_then(|(lhs_num, rhs_num)| lhs_num.checked_add(rhs_num));
+        let den = (self.den / den_gcd).checked_mul(rhs.den);
+
+        match (num, den) {
+            (Some(num), Some(den)) => Self::reduced(num, den),
+            // cross multiplication is exact in 128 bits
+            _ => Self::fit(
+                self.num as i128 * rhs.den as i128 + rhs.num as i128 * self.den as i128,
+                self.den as i128 * rhs.den as i128,
+            ),
         }
     }
 }
@@ -219,50 +245,41 @@ impl ops::Add<Rat> for Rat {
 impl ops::Sub<Rat> for Rat {
     type Output = Self;
 
-    fn sub(self, mut rhs: Self) -> Self {
-        if rhs.num == i64::MIN {
-            rhs.num >>= 1;
-            rhs.den >>= 1;
+    fn sub(self, rhs: Self) -> Self {
+        match rhs.num.checked_neg() {
+            Some(num) => self + Rat { num, den: rhs.den },
+            None => Self::fit(
+                self.num as i128 * rhs.den as i128 - rhs.num as i128 * self.den as i128,
+                self.den as i128 * rhs.den as i128,
+            ),
         }
-
-        rhs.num *= -1;
-
-        self + rhs
     }
 }
 
 impl ops::Mul<Rat> for Rat {
     type Output = Self;
 
-    fn mul(mut self, mut rhs: Self) -> Self {
-        loop {
-            if self.num == i64::MIN || self.den == i64::MIN {
-                self.num >>= 1;
-                self.den >>= 1;
-            }
-
-            if rhs.num == i64::MIN || rhs.den == i64::MIN {
-                rhs.num >>= 1;
-                rhs.den >>= 1;
-            }
-
-            let lhs_gcd = gcd(self.num, rhs.den);
-            let rhs_gcd = gcd(rhs.num, self.den);
-
-            let num = (self.num / lhs_gcd).checked_mul(rhs.num / rhs_gcd);
-            let den = (self.den / rhs_gcd).checked_mul(rhs.den / lhs_gcd);
-
-            if let (Some(num), Some(den)) = (num, den) {
-                return Self { num, den };
-            } else if self.num == i64::MIN
-                || (rhs.num != i64::MIN && self.num.abs() > rhs.num.abs())
-            {
-                self.num >>= 1;
-                self.den >>= 1;
-            } else {
-                rhs.num >>= 1;
-                rhs.den >>= 1;
-            }
+    fn mul(self, rhs: Self) -> Self {
+        let wide = || {
+            Self::fit(
+                self.num as i128 * rhs.num as i128,
+                self.den as i128 * rhs.den as i128,
+            )
+        };
+
+        if [self.num, self.den, rhs.num, rhs.den].contains(&i64::MIN) {
+            return wide();
+        }
+
+        let lhs_gcd = gcd(self.num, rhs.den);
+        let rhs_gcd = gcd(rhs.num, self.den);
+
+        let num = (self.num / lhs_gcd).checked_mul(rhs.num / rhs_gcd);
+        let den = (self.den / rhs_gcd).checked_mul(rhs.den / lhs_gcd);
+
+        match (num, den) {
+            (Some(num), Some(den)) => Self::reduced(num, den),
+            _ => wide(),
         }
     }
 }
@@ -270,56 +287,67 @@ impl ops::Mul<Rat> for Rat {
 impl ops::Mul<i64> for Rat {
     type Output = Self;
 
-    fn mul(mut self, rhs: i64) -> Self {
-        if self.den % rhs == 0 {
-            self.den /= rhs;
-        } else {
-            self.num *= rhs;
-        }
-
-        self
+    fn mul(self, rhs: i64) -> Self {
+        self * Rat::from(rhs)
     }
 }
 
 impl ops::Div<Rat> for Rat {
     type Output = Self;
 
-    fn div(mut self, mut rhs: Self) -> Self {
-        loop {
-            if self.num == i64::MIN || self.den == i64::MIN {
-                self.num >>= 1;
-                self.den >>= 1;
-            }
-
-            if rhs.num == i64::MIN || rhs.den == i64::MIN {
-                rhs.num >>= 1;
-                rhs.den >>= 1;
-            }
-
-            let num_gcd = gcd(self.num, rhs.num);
-            let den_gcd = gcd(rhs.den, self.den);
-
-            let num = (self.num / num_gcd).checked_mul(rhs.den / den_gcd);
-            let den = (self.den / den_gcd).checked_mul(rhs.num / num_gcd);
-
-            if let (Some(num), Some(den)) = (num, den) {
-                return Self { num, den };
-            } else if self.num == i64::MIN
-                || (rhs.den != i64::MIN && self.num.abs() > rhs.den.abs())
-            {
-                self.num >>= 1;
-                self.den >>= 1;
-            } else {
-                rhs.num >>= 1;
-                rhs.den >>= 1;
-            }
+    fn div(self, rhs: Self) -> Self {
+        let wide = || {
+            Self::fit(
+                self.num as i128 * rhs.den as i128,
+                self.den as i128 * rhs.num as i128,
+            )
+        };
+
+        if [self.num, self.den, rhs.num, rhs.den].contains(&i64::MIN) {
+            return wide();
+        }
+
+        let num_gcd = gcd(self.num, rhs.num);
+        let den_gcd = gcd(rhs.den, self.den);
+
+        let num = (self.num / num_gcd).checked_mul(rhs.den / den_gcd);
+        let den = (self.den / den_gcd).checked_mul(rhs.num / num_gcd);
+
+        match (num, den) {
+            (Some(num), Some(den)) => Self::reduced(num, den),
+            _ => wide(),
         }
     }
 }
 
-impl std::string::ToString for Rat {
-    fn to_string(&self) -> String {
-        f64::from(*self).to_string()
+impl fmt::Display for Rat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", f64::from(*self))
+    }
+}
+
+impl field::Fraction for Rat {
+    // a denominator lcm past i64 is dropped, which keeps the result positive but rounded
+    fn primitive(vals: &[Self]) -> Self {
+        let den_lcm = vals
+            .iter()
+            .try_fold(1i64, |acc, val| (acc / gcd(acc, val.den)).checked_mul(val.den))
+            .unwrap_or_else(|| {
+                INEXACT.set(true);
+                1
+            });
+        let num_gcd = vals
+            .iter()
+            .filter(|val| !val.is_zero())
+            .fold(0, |acc, val| if acc == 0 { val.num } else { gcd(acc, val.num) });
+
+        if num_gcd == 0 {
+            Rat::from(1)
+        } else {
+            // the division rounds and flags an i64::MIN numerator
+            let scale = Rat::from(num_gcd) / Rat::from(den_lcm);
+            if scale < Rat::from(0) { scale * -1 } else { scale }
+        }
     }
 }
 
@@ -359,10 +387,19 @@ pub fn gcd(mut a: i64, mut b: i64) -> i64 {
     a << shift
 }
 
+// Euclid's algorithm on magnitudes too wide for i64
+fn gcd_wide(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    a
+}
+
 #[cfg(test)]
 mod tests {
     use super::gcd;
-    use super::Rat;
+    use super::{exact, Inexact, Rat};
     use rand::prelude::*;
     use std::cmp::Ordering;
 
@@ -379,6 +416,20 @@ mod tests {
         assert_eq!(((a + b) * (a - b) + b).num, -1);
     }
 
+    #[test]
+    fn canonical() {
+        let a = Rat::from(3) / Rat::from(-6);
+        assert_eq!(a, Rat { num: -1, den: 2 });
+
+        let b = Rat::from(2) * -1;
+        assert_eq!(b, Rat { num: -2, den: 1 });
+
+        let c = a - a;
+        assert_eq!(c, Rat { num: 0, den: 1 });
+
+        assert_eq!(Ordering::Less, (Rat::from(1) / Rat::from(-3)).cmp(&Rat::from(0)));
+    }
+
     #[test]
     fn overflow() {
         let a = Rat {
@@ -394,6 +445,33 @@ mod tests {
         assert_eq!(f64::from(a) + f64::from(b), f64::from(c));
     }
 
+    #[test]
+    fn inexact() {
+        let big = Rat::from(i64::MAX) / Rat::from(3);
+
+        assert_eq!(Ok(Rat::from(5)), exact(|| Rat::from(2) + Rat::from(3)));
+        assert_eq!(Err(Inexact), exact(|| big * big));
+        assert_eq!(None, big.checked_add(Rat::from(1) / Rat::from(7)));
+        assert_eq!(Some(Rat::from(1)), big.checked_div(big));
+
+        // the flag is scoped, so an outer computation still sees an inner rounding
+        let outer = exact(|| {
+            let inner = exact(|| Rat::from(1) + Rat::from(1));
+            let _ = big * big;
+            inner
+        });
+
+        assert_eq!(Err(Inexact), outer);
+
+        // a checked operation reports its own rounding and leaves the enclosing flag alone
+        let handled = exact(|| {
+            let _ = Rat::from(i64::MAX).checked_mul(Rat::from(3));
+            1
+        });
+
+        assert_eq!(Ok(1), handled);
+    }
+
     #[test]
     fn gcd_shifts() {
         let a = 16 * 74;
@@ -449,5 +527,35 @@ mod tests {
                 f64::from(d)
             );
         }
+
+        // integer operands have no denominator bits to drop, so these saturate
+        let max = Rat::from(i64::MAX);
+        assert_eq!(Err(Inexact), exact(|| max * Rat::from(3)));
+        assert_eq!(max, max * Rat::from(3));
+        assert_eq!(max * -1, max * Rat::from(-3));
+        assert_eq!(max, max + max);
+        assert!(f64::from(max - Rat::from(1) / Rat::from(3)).is_finite());
+    }
+
+    #[test]
+    fn min_parts() {
+        let min = Rat::from(i64::MIN);
+        let half = Rat::from(1 << 62);
+
+        assert_eq!(Ok(Rat::from(0)), exact(|| min - min));
+        assert_eq!(Ok(Rat::from(-(1 << 62))), exact(|| min + half));
+
+        // -2^62 / (-1 / 2) = 2^63 does not fit, and saturates instead of panicking
+        let neg_half = Rat::from(-1) / Rat::from(2);
+        assert_eq!(Err(Inexact), exact(|| half * -1 / neg_half));
+        assert_eq!(Rat::from(i64::MAX), half * -1 / neg_half);
+
+        // 1 / -2^63 drops below the smallest denominator step and rounds to a canonical zero
+        assert_eq!(Err(Inexact), exact(|| Rat::from(1) / half / Rat::from(-2)));
+        assert_eq!(Rat::from(0), Rat::from(1) / half / Rat::from(-2));
+
+        // negating i64::MIN itself rounds
+        assert_eq!(Err(Inexact), exact(|| min * -1));
+        assert_eq!(Rat::from(i64::MAX), min * -1);
     }
 }
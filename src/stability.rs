@@ -0,0 +1,133 @@
+// where all the roots lie, decided exactly without isolating any of them
+use crate::bigrat::BigRat;
+use crate::field::Fraction;
+use crate::rational::Rat;
+use crate::univariate::UPoly;
+
+impl UPoly<Rat> {
+    // routh-hurwitz: whether every root has negative real part; the zero polynomial is not
+    // stable. computed exactly, since the routh array outgrows a Rat
+    pub fn is_hurwitz_stable(&self) -> bool {
+        self.map(|c| BigRat::from(*c)).routh_hurwitz()
+    }
+
+    // schur-cohn: whether every root lies inside the unit circle; the zero polynomial is not
+    // stable. computed exactly, like is_hurwitz_stable
+    pub fn is_schur_stable(&self) -> bool {
+        self.map(|c| BigRat::from(*c)).schur_cohn()
+    }
+}
+
+impl<T: Fraction> UPoly<T> {
+    // read off the first column of the routh array
+    fn routh_hurwitz(&self) -> bool {
+        let p = self.clone().trim();
+
+        if p.is_zero() {
+            return false;
+        }
+
+        let positive = p.0[0] > T::zero();
+        let mut prev = p.0.iter().step_by(2).cloned().collect::<Vec<_>>();
+        let mut row = p.0.iter().skip(1).step_by(2).cloned().collect::<Vec<_>>();
+
+        for _ in 0..p.deg() {
+            let lead = row.first().cloned().unwrap_or_else(T::zero);
+
+            if lead.is_zero() || (lead > T::zero()) != positive {
+                return false;
+            }
+
+            let at = |v: &[T], j: usize| v.get(j).cloned().unwrap_or_else(T::zero);
+            let ratio = prev[0].clone() / lead;
+            let next = (0..prev.len().max(row.len()) - 1)
+                .map(|j| at(&prev, j + 1) - ratio.clone() * at(&row, j + 1))
+                .collect();
+
+            prev = row;
+            row = next;
+        }
+
+        true
+    }
+
+    // each step keeps the roots' positions relative to the circle and lowers the degree by one
+    fn schur_cohn(&self) -> bool {
+        let mut p = self.clone().trim();
+
+        if p.is_zero() {
+            return false;
+        }
+
+        while p.deg() > 0 {
+            let (lc, constant) = (p.0[0].clone(), p.0[p.0.len() - 1].clone());
+
+            if constant.clone() * constant.clone() >= lc.clone() * lc.clone() {
+                return false;
+            }
+
+            // (lc p(z) - constant z^n p(1 / z)) / z, whose coefficients would double in size
+            // each step without dividing out their common factor
+            let mut next = p
+                .0
+                .iter()
+                .zip(p.0.iter().rev())
+                .map(|(c, reversed)| lc.clone() * c.clone() - constant.clone() * reversed.clone())
+                .collect::<Vec<_>>();
+            next.pop();
+
+            let scale = T::primitive(&next);
+            p = UPoly(next.into_iter().map(|c| c / scale.clone()).collect());
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::univariate::upoly;
+
+    #[test]
+    fn hurwitz() {
+        // (s + 1)(s^2 + s + 1)
+        assert!(upoly(&[1, 2, 2, 1]).is_hurwitz_stable());
+        assert!(upoly(&[-1, -2, -2, -1]).is_hurwitz_stable());
+        assert!(upoly(&[1, 2, 1]).is_hurwitz_stable());
+        assert!(upoly(&[3]).is_hurwitz_stable());
+
+        // (s - 1)(s + 2), s^2 + 1 on the axis, and s^3 + s^2 + 2s + 8 with roots 1/2 +- 1.9i
+        assert!(!upoly(&[1, 1, -2]).is_hurwitz_stable());
+        assert!(!upoly(&[1, 0, 1]).is_hurwitz_stable());
+        assert!(!upoly(&[1, 1, 2, 8]).is_hurwitz_stable());
+        assert!(!upoly(&[1, 0]).is_hurwitz_stable());
+        assert!(!upoly(&[]).is_hurwitz_stable());
+
+        // (s + 1)...(s + 8), and with s - 1 in place of s + 8
+        let stable = (1..=8).fold(upoly(&[1]), |acc, k| acc * upoly(&[1, k]));
+        assert!(stable.is_hurwitz_stable());
+
+        let unstable = (1..=7).fold(upoly(&[1, -1]), |acc, k| acc * upoly(&[1, k]));
+        assert!(!unstable.is_hurwitz_stable());
+    }
+
+    #[test]
+    fn schur() {
+        // (2z - 1)(3z + 1), and 4z^2 + 1 with roots +- i / 2
+        assert!(upoly(&[6, -1, -1]).is_schur_stable());
+        assert!(upoly(&[4, 0, 1]).is_schur_stable());
+        assert!(upoly(&[5]).is_schur_stable());
+
+        // z - 2, and z^2 - z + 1 with roots on the circle
+        assert!(!upoly(&[1, -2]).is_schur_stable());
+        assert!(!upoly(&[1, -1, 1]).is_schur_stable());
+        assert!(!upoly(&[1, 0, 0, -1]).is_schur_stable());
+
+        // (10z - 1)...(10z - 8) has every root inside, (10z - 1)...(10z - 11) one outside
+        let inside = (1..=8).fold(upoly(&[1]), |acc, k| acc * upoly(&[10, -k]));
+        assert!(inside.is_schur_stable());
+
+        let outside = (1..=11).fold(upoly(&[1]), |acc, k| acc * upoly(&[10, -k]));
+        assert!(!outside.is_schur_stable());
+    }
+}
@@ -1,9 +1,13 @@
-use std::cmp::Ordering;
+use std::cmp::{max, min, Ordering};
+use std::ops;
 
 // thanks to Osvaldo Carvalho
 // https://www.researchgate.net/publication/320864673_A_simple_recursive_algorithm_to_find_all_real_roots_of_a_polynomial
-use crate::field::Field;
-use crate::rational::Rat;
+use crate::bigrat::BigRat;
+use crate::field::{simplest_between, Field};
+use crate::poly::mono::Mono;
+use crate::poly::Poly;
+use crate::rational::{exact, Inexact, Rat};
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct UPoly<T: Field>(pub Vec<T>);
@@ -21,6 +25,13 @@ impl<T: Field> Root<T> {
             Root::Interval(start, end) => (start.clone() + end.clone()) / T::from(2),
         }
     }
+
+    pub fn bounds(&self) -> (T, T) {
+        match self {
+            Root::Point(p) => (p.clone(), p.clone()),
+            Root::Interval(start, end) => (start.clone(), end.clone()),
+        }
+    }
 }
 
 impl<T: Field> UPoly<T> {
@@ -32,6 +43,10 @@ impl<T: Field> UPoly<T> {
     }
 
     pub fn derivative(&self) -> Self {
+        if self.0.len() <= 1 {
+            return Self(vec![]);
+        }
+
         let mut new = self.0.clone();
         new.pop();
         let deg = new.len() - 1;
@@ -43,6 +58,7 @@ impl<T: Field> UPoly<T> {
         Self(new)
     }
 
+    // expects a squarefree self; repeated irrational roots can be missed, see real_root_multiplicities
     pub fn real_root_intervals(&self, tolerance: T) -> Vec<Root<T>> {
         match self.0.len() {
             0 | 1 => vec![],
@@ -64,7 +80,7 @@ impl<T: Field> UPoly<T> {
 
                 match self.eval(&first_derivative_root).cmp(&T::zero()) {
                     Ordering::Less => {
-                        if (self.0[0] < T::zero()) ^ (self.0.len() % 2 == 0) {
+                        if (self.0[0] < T::zero()) ^ self.0.len().is_multiple_of(2) {
                             // value here is same sign as -inf; no root
                         } else {
                             // probe backwards until we have a finite interval
@@ -74,7 +90,7 @@ impl<T: Field> UPoly<T> {
                             {
                                 lhs = lhs.clone() * T::from(2);
                             }
-                            new_roots.push(self.refine_root_interval(
+                            new_roots.push(self.probed_root(
                                 &derivative,
                                 first_derivative_root.clone() + lhs,
                                 first_derivative_root,
@@ -83,7 +99,7 @@ impl<T: Field> UPoly<T> {
                         }
                     }
                     Ordering::Greater => {
-                        if !((self.0[0] < T::zero()) ^ (self.0.len() % 2 == 0)) {
+                        if !((self.0[0] < T::zero()) ^ self.0.len().is_multiple_of(2)) {
                             // value here is same sign as -inf; no root
                         } else {
                             // probe backwards until we have a finite interval
@@ -93,7 +109,7 @@ impl<T: Field> UPoly<T> {
                             {
                                 lhs = lhs.clone() * T::from(2);
                             }
-                            new_roots.push(self.refine_root_interval(
+                            new_roots.push(self.probed_root(
                                 &derivative,
                                 first_derivative_root.clone() + lhs,
                                 first_derivative_root,
@@ -144,7 +160,7 @@ impl<T: Field> UPoly<T> {
                             {
                                 lhs = lhs.clone() * T::from(2);
                             }
-                            new_roots.push(self.refine_root_interval(
+                            new_roots.push(self.probed_root(
                                 &derivative,
                                 last_derivative_root.clone(),
                                 last_derivative_root + lhs,
@@ -163,7 +179,7 @@ impl<T: Field> UPoly<T> {
                             {
                                 lhs = lhs.clone() * T::from(2);
                             }
-                            new_roots.push(self.refine_root_interval(
+                            new_roots.push(self.probed_root(
                                 &derivative,
                                 last_derivative_root.clone(),
                                 last_derivative_root + lhs,
@@ -179,6 +195,76 @@ impl<T: Field> UPoly<T> {
         }
     }
 
+    // roots of every squarefree factor, ascending, with the factor's multiplicity; roots of
+    // different factors are refined until their intervals are disjoint
+    pub fn real_root_multiplicities(&self, tolerance: T) -> Vec<(Root<T>, usize)> {
+        let factors = self
+            .squarefree_decomposition()
+            .into_iter()
+            .map(|(factor, multiplicity)| (factor.derivative(), factor, multiplicity))
+            .collect::<Vec<_>>();
+
+        let mut roots = factors
+            .iter()
+            .enumerate()
+            .flat_map(|(i, (_, factor, _))| {
+                factor
+                    .real_root_intervals(tolerance.clone())
+                    .into_iter()
+                    .map(move |root| (root, i))
+            })
+            .collect::<Vec<_>>();
+
+        // the factors are coprime, so halving every overlapping interval eventually separates them
+        let overlap = |lhs: &Root<T>, rhs: &Root<T>| {
+            let ((lhs_lo, lhs_hi), (rhs_lo, rhs_hi)) = (lhs.bounds(), rhs.bounds());
+            lhs_lo < rhs_hi && rhs_lo < lhs_hi
+        };
+
+        loop {
+            let overlapping = (0..roots.len())
+                .filter(|i| {
+                    roots
+                        .iter()
+                        .any(|(other, j)| *j != roots[*i].1 && overlap(&roots[*i].0, other))
+                })
+                .collect::<Vec<_>>();
+
+            if overlapping.is_empty() {
+                break;
+            }
+
+            for i in overlapping {
+                if let (Root::Interval(start, end), factor) = &roots[i] {
+                    let (derivative, p, _) = &factors[*factor];
+                    let width = (end.clone() - start.clone()) / T::from(2);
+                    let refined =
+                        p.refine_root_interval(derivative, start.clone(), end.clone(), width);
+
+                    roots[i].0 = refined;
+                }
+            }
+        }
+
+        roots.sort_by_key(|(root, _)| root.approx());
+
+        roots
+            .into_iter()
+            .map(|(root, i)| (root, factors[i].2))
+            .collect()
+    }
+
+    // the probe that closed an unbounded interval may have landed on the root itself
+    fn probed_root(&self, derivative: &UPoly<T>, start: T, end: T, tolerance: T) -> Root<T> {
+        if self.eval(&start).is_zero() {
+            Root::Point(start)
+        } else if self.eval(&end).is_zero() {
+            Root::Point(end)
+        } else {
+            self.refine_root_interval(derivative, start, end, tolerance)
+        }
+    }
+
     pub fn refine_root_interval(
         &self,
         derivative: &UPoly<T>,
@@ -201,40 +287,35 @@ impl<T: Field> UPoly<T> {
                 return Root::Point(mid);
             }
 
-            let candidate1 = mid.clone() - mid_eval.clone() / start_deriv;
-            let candidate1_eval = self.eval(&candidate1);
-
-            if candidate1_eval == T::zero() {
-                return Root::Point(candidate1);
-            }
+            let window = (end.clone() - start.clone()) / T::from(8);
+            let mut progress = false;
 
-            let candidate2 = mid - mid_eval / end_deriv;
-            let candidate2_eval = self.eval(&candidate2);
+            for deriv in [start_deriv, end_deriv] {
+                if deriv.is_zero() {
+                    continue;
+                }
 
-            if candidate2_eval == T::zero() {
-                return Root::Point(candidate2);
-            }
+                let candidate = mid.clone() - mid_eval.clone() / deriv;
 
-            let mut progress = false;
+                if candidate > start && candidate < end {
+                    // snap to a nearby dyadic so exact evaluation stays cheap
+                    let candidate = simplest_between(
+                        &max(start.clone(), candidate.clone() - window.clone()),
+                        &min(end.clone(), candidate + window.clone()),
+                    );
+                    let candidate_eval = self.eval(&candidate);
 
-            if candidate1 > start && candidate1 < end {
-                let candidate1_sign = self.eval(&candidate1) > T::zero();
-                if candidate1_sign == start_sign {
-                    start = candidate1;
-                } else {
-                    end = candidate1;
-                }
-                progress = true;
-            }
+                    if candidate_eval == T::zero() {
+                        return Root::Point(candidate);
+                    }
 
-            if candidate2 > start && candidate2 < end {
-                let candidate2_sign = self.eval(&candidate2) > T::zero();
-                if candidate2_sign == start_sign {
-                    start = candidate2;
-                } else {
-                    end = candidate2;
+                    if (candidate_eval > T::zero()) == start_sign {
+                        start = candidate;
+                    } else {
+                        end = candidate;
+                    }
+                    progress = true;
                 }
-                progress = true;
             }
 
             if !progress {
@@ -264,7 +345,401 @@ impl<T: Field> UPoly<T> {
     }
 }
 
+impl<T: Field> UPoly<T> {
+    pub fn from_poly(p: &Poly<T>, var: usize) -> Self {
+        Self(
+            p.coefs(var)
+                .into_iter()
+                .map(|coef| match coef.terms.as_slice() {
+                    [] => T::zero(),
+                    [Mono { val, vars }] if vars.is_empty() => val.clone(),
+                    _ => panic!("polynomial is not univariate in variable {}", var),
+                })
+                .collect(),
+        )
+        .trim()
+    }
+
+    pub fn to_poly(&self, var: usize) -> Poly<T> {
+        self.0
+            .iter()
+            .fold(Poly::constant(T::zero()), |acc, coef| {
+                acc * Poly::var(var, 1) + Poly::constant(coef.clone())
+            })
+    }
+
+    // without leading zero coefficients; the zero polynomial has none at all
+    pub fn trim(mut self) -> Self {
+        let leading_zeros = self.0.iter().take_while(|c| c.is_zero()).count();
+        self.0.drain(0..leading_zeros);
+        self
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.iter().all(|c| c.is_zero())
+    }
+
+    pub fn map<U: Field>(&self, f: impl Fn(&T) -> U) -> UPoly<U> {
+        UPoly(self.0.iter().map(f).collect())
+    }
+
+    // zero for constants and the zero polynomial
+    pub fn deg(&self) -> usize {
+        let trimmed = self.clone().trim();
+        trimmed.0.len().max(1) - 1
+    }
+
+    pub fn monic(&self) -> Self {
+        let trimmed = self.clone().trim();
+
+        match trimmed.0.first() {
+            Some(lc) => {
+                let lc = lc.clone();
+                Self(trimmed.0.into_iter().map(|c| c / lc.clone()).collect())
+            }
+            None => trimmed,
+        }
+    }
+
+    pub fn div_rem(&self, divisor: &UPoly<T>) -> (UPoly<T>, UPoly<T>) {
+        let divisor = divisor.clone().trim();
+        let mut rem = self.clone().trim().0;
+
+        if divisor.0.is_empty() {
+            panic!("polynomial division by zero");
+        }
+
+        if rem.len() < divisor.0.len() {
+            return (UPoly(vec![]), UPoly(rem));
+        }
+
+        let mut quot = vec![T::zero(); rem.len() - divisor.0.len() + 1];
+
+        for i in 0..quot.len() {
+            let coef = rem[i].clone() / divisor.0[0].clone();
+
+            for (j, d) in divisor.0.iter().enumerate() {
+                rem[i + j] = rem[i + j].clone() - coef.clone() * d.clone();
+            }
+
+            quot[i] = coef;
+        }
+
+        let rem = rem.split_off(quot.len());
+
+        (UPoly(quot), UPoly(rem).trim())
+    }
+
+    // monic, so the result is canonical
+    pub fn gcd(&self, other: &UPoly<T>) -> UPoly<T> {
+        let mut a = self.clone().trim();
+        let mut b = other.clone().trim();
+
+        while !b.0.is_empty() {
+            let (_, rem) = a.div_rem(&b);
+            a = b;
+            b = rem;
+        }
+
+        a.monic()
+    }
+
+    // same roots, each with multiplicity one
+    pub fn squarefree_part(&self) -> UPoly<T> {
+        let g = self.gcd(&self.derivative());
+
+        if g.0.is_empty() {
+            return self.monic();
+        }
+
+        self.div_rem(&g).0.monic()
+    }
+
+    // Yun's algorithm: monic, pairwise coprime squarefree factors with their
+    // multiplicities, whose product is self up to its leading coefficient
+    pub fn squarefree_decomposition(&self) -> Vec<(UPoly<T>, usize)> {
+        let p = self.clone().trim();
+
+        if p.deg() == 0 {
+            return vec![];
+        }
+
+        let derivative = p.derivative();
+        let g = p.gcd(&derivative);
+
+        let mut w = p.div_rem(&g).0;
+        let mut y = derivative.div_rem(&g).0;
+        let mut factors = vec![];
+
+        for multiplicity in 1.. {
+            let z = y - w.derivative();
+            let factor = w.gcd(&z);
+
+            if factor.deg() > 0 {
+                factors.push((factor.clone(), multiplicity));
+            }
+
+            w = w.div_rem(&factor).0;
+
+            if w.deg() == 0 {
+                break;
+            }
+
+            y = z.div_rem(&factor).0;
+        }
+
+        factors
+    }
+
+    // p, p' and then the negated remainders, down to the gcd of p and p'
+    pub fn sturm_sequence(&self) -> Vec<UPoly<T>> {
+        if self.is_zero() {
+            return vec![];
+        }
+
+        self.signed_remainders(&self.derivative())
+    }
+
+    // hermite: distinct real roots where q is positive, from the signatures of the quadratic
+    // forms of q and q^2; the power sums grow fast, so T must be exact
+    fn hermite_count(&self, q: &UPoly<T>) -> usize {
+        let signs = self.hermite_signature(q);
+        let nonzero = self.hermite_signature(&(q.clone() * q.clone()));
+
+        usize::try_from((nonzero + signs) / 2).expect("exact signatures give a nonnegative count")
+    }
+
+    // sum of the signs of q over the distinct real roots: the signature of the form with
+    // entries sum_roots q(x) x^(i + j), which power sums give exactly
+    fn hermite_signature(&self, q: &UPoly<T>) -> i64 {
+        let p = self.monic();
+        let n = p.deg();
+
+        if n == 0 {
+            return 0;
+        }
+
+        // newton's identities: power sums of the roots, counted with multiplicity
+        let q = q.clone().trim();
+        let mut sums = vec![T::from(n as i64)];
+
+        for k in 1..2 * n + q.0.len() {
+            let mut sum = if k <= n { p.0[k].clone() * k as i64 } else { T::zero() };
+
+            for i in 1..=n.min(k - 1) {
+                sum = sum + p.0[i].clone() * sums[k - i].clone();
+            }
+
+            sums.push(T::zero() - sum);
+        }
+
+        let form = (0..n)
+            .map(|i| {
+                (0..n)
+                    .map(|j| {
+                        q.0.iter()
+                            .rev()
+                            .enumerate()
+                            .fold(T::zero(), |acc, (k, c)| acc + c.clone() * sums[i + j + k].clone())
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        // symmetric, so its characteristic polynomial has only real roots and descartes'
+        // rule counts the positive and negative eigenvalues exactly
+        let charpoly = characteristic_polynomial(&form);
+        let mirrored = charpoly
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(i, c)| if i % 2 == 0 { c.clone() } else { T::zero() - c.clone() })
+            .collect::<Vec<_>>();
+        let variations = |coefs: &[T]| sign_variations(coefs.iter().map(|c| c.cmp(&T::zero()))) as i64;
+
+        variations(&charpoly) - variations(&mirrored)
+    }
+
+    // self, q and then the negated remainders, down to the gcd of self and q
+    pub fn signed_remainders(&self, q: &UPoly<T>) -> Vec<UPoly<T>> {
+        let mut seq = vec![self.clone().trim(), q.clone().trim()];
+
+        while !seq[seq.len() - 1].is_zero() {
+            let (_, rem) = seq[seq.len() - 2].div_rem(&seq[seq.len() - 1]);
+            seq.push(UPoly(vec![]) - rem);
+        }
+
+        seq.pop();
+
+        seq
+    }
+
+    // cauchy index of q / self over (a, b): jumps from -inf to inf less those from inf to -inf;
+    // a and b must not be roots of self
+    pub fn cauchy_index(&self, q: &UPoly<T>, a: &T, b: &T) -> i64 {
+        let seq = self.signed_remainders(q);
+        let at = |x: &T| sign_variations(seq.iter().map(|p| p.eval(x).cmp(&T::zero()))) as i64;
+
+        at(a) - at(b)
+    }
+
+    // distinct real roots in (a, b]; the zero polynomial counts none
+    pub fn count_roots_in(&self, a: &T, b: &T) -> usize {
+        if a >= b {
+            return 0;
+        }
+
+        let seq = self.squarefree_part().sturm_sequence();
+        let at = |x: &T| sign_variations(seq.iter().map(|p| p.eval(x).cmp(&T::zero())));
+
+        at(a) - at(b)
+    }
+
+    // distinct real roots; the zero polynomial counts none
+    pub fn count_real_roots(&self) -> usize {
+        let seq = self.squarefree_part().sturm_sequence();
+        let at_inf = |neg: bool| {
+            sign_variations(seq.iter().map(|p| {
+                let sign = p.0[0].cmp(&T::zero());
+
+                if neg && p.deg() % 2 == 1 {
+                    sign.reverse()
+                } else {
+                    sign
+                }
+            }))
+        };
+
+        at_inf(true) - at_inf(false)
+    }
+
+    // extended Euclid; none when self and m share a factor
+    pub fn inverse_mod(&self, m: &UPoly<T>) -> Option<UPoly<T>> {
+        let (mut a, mut b) = (m.clone().trim(), self.div_rem(m).1);
+        let (mut a_coef, mut b_coef) = (UPoly(vec![]), UPoly(vec![T::one()]));
+
+        while !b.0.is_empty() {
+            let (quot, rem) = a.div_rem(&b);
+            let next_coef = a_coef - quot * b_coef.clone();
+
+            a = b;
+            b = rem;
+            a_coef = b_coef;
+            b_coef = next_coef;
+        }
+
+        if a.0.len() != 1 {
+            return None;
+        }
+
+        let scale = UPoly(vec![T::one() / a.0[0].clone()]);
+
+        Some((a_coef * scale).div_rem(m).1)
+    }
+}
+
+// faddeev-leverrier: det(x - a), descending
+fn characteristic_polynomial<T: Field>(a: &[Vec<T>]) -> Vec<T> {
+    let n = a.len();
+    let mut coefs = vec![T::one()];
+    let mut m = vec![vec![T::zero(); n]; n];
+
+    for k in 1..=n {
+        // m = a m + c I, with c the latest coefficient
+        let c = coefs[k - 1].clone();
+        m = (0..n)
+            .map(|i| {
+                (0..n)
+                    .map(|j| {
+                        let product = (0..n).fold(T::zero(), |acc, l| acc + a[i][l].clone() * m[l][j].clone());
+                        if i == j { product + c.clone() } else { product }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let trace = (0..n).fold(T::zero(), |acc, i| {
+            acc + (0..n).fold(T::zero(), |acc, l| acc + a[i][l].clone() * m[l][i].clone())
+        });
+
+        coefs.push(T::zero() - trace / T::from(k as i64));
+    }
+
+    coefs
+}
+
+// sign changes along a sequence, skipping zeros
+pub(crate) fn sign_variations(signs: impl Iterator<Item = Ordering>) -> usize {
+    let signs = signs.filter(|sign| *sign != Ordering::Equal).collect::<Vec<_>>();
+
+    signs.windows(2).filter(|pair| pair[0] != pair[1]).count()
+}
+
+impl<T: Field> ops::Add<UPoly<T>> for UPoly<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        let (mut long, short) = if self.0.len() >= rhs.0.len() {
+            (self.0, rhs.0)
+        } else {
+            (rhs.0, self.0)
+        };
+
+        let offset = long.len() - short.len();
+
+        for (i, coef) in short.into_iter().enumerate() {
+            long[offset + i] = long[offset + i].clone() + coef;
+        }
+
+        UPoly(long).trim()
+    }
+}
+
+impl<T: Field> ops::Sub<UPoly<T>> for UPoly<T> {
+    type Output = Self;
+
+    fn sub(self, mut rhs: Self) -> Self {
+        for coef in &mut rhs.0 {
+            *coef = coef.clone() * -1;
+        }
+
+        self + rhs
+    }
+}
+
+impl<T: Field> ops::Mul<UPoly<T>> for UPoly<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        if self.0.is_empty() || rhs.0.is_empty() {
+            return UPoly(vec![]);
+        }
+
+        let mut coefs = vec![T::zero(); self.0.len() + rhs.0.len() - 1];
+
+        for (i, lhs_coef) in self.0.iter().enumerate() {
+            for (j, rhs_coef) in rhs.0.iter().enumerate() {
+                coefs[i + j] = coefs[i + j].clone() + lhs_coef.clone() * rhs_coef.clone();
+            }
+        }
+
+        UPoly(coefs).trim()
+    }
+}
+
 impl UPoly<Rat> {
+    // distinct real roots where q is positive, counted exactly; the zero polynomial counts none
+    pub fn count_real_roots_where_positive(&self, q: &UPoly<Rat>) -> usize {
+        let big = |c: &Rat| BigRat::from(*c);
+
+        self.map(big).hermite_count(&q.map(big))
+    }
+
+    // fails instead of returning intervals computed with rounded arithmetic
+    pub fn try_real_root_intervals(&self, tolerance: Rat) -> Result<Vec<Root<Rat>>, Inexact> {
+        exact(|| self.real_root_intervals(tolerance))
+    }
+
     pub fn real_roots(&self, tolerance: f64) -> Vec<f64> {
         let mut tolerance_rat = Rat::from(1);
         while f64::from(tolerance_rat) > tolerance {
@@ -278,6 +753,12 @@ impl UPoly<Rat> {
     }
 }
 
+// integer coefficients, leading first
+#[cfg(test)]
+pub fn upoly(coefs: &[i64]) -> UPoly<Rat> {
+    UPoly(coefs.iter().map(|c| Rat::from(*c)).collect())
+}
+
 #[macro_export]
 macro_rules! univariate {
     ( $($t:tt)* ) => ({
@@ -318,7 +799,9 @@ macro_rules! univariate {
 
 #[cfg(test)]
 mod tests {
-    use super::{Root, UPoly};
+    use super::{upoly, Root, UPoly};
+    use crate::bigrat::BigRat;
+    use crate::field::Zero;
     use crate::rational::Rat;
 
     #[test]
@@ -372,6 +855,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn inexact_roots() {
+        let tol = Rat::from(1) / Rat::from(10000);
+
+        let quadratic = UPoly(vec![Rat::from(1), Rat::from(0), Rat::from(-2)]);
+        assert_eq!(2, quadratic.try_real_root_intervals(tol).unwrap().len());
+
+        // roots near 10^12 need bounds whose powers overflow
+        let wide = UPoly(vec![Rat::from(1), Rat::from(-2_000_000_000_001), Rat::from(3)]);
+        assert!(wide.try_real_root_intervals(tol).is_err());
+    }
+
     #[test]
     fn big_root() {
         let poly = UPoly(vec![
@@ -391,4 +886,107 @@ mod tests {
         assert!((roots_f[2] - 3.).abs() < f64::from(tol));
         assert!((roots_f[3] - 5.).abs() < f64::from(tol));
     }
+
+    #[test]
+    fn huge_root() {
+        // x^2 - 3 * 10^40, whose roots overflow i64
+        let big = BigRat::from(3) * (0..40).fold(BigRat::from(1), |acc, _| acc * BigRat::from(10));
+        let poly = UPoly(vec![BigRat::from(1), BigRat::zero(), BigRat::zero() - big]);
+
+        let roots = poly.real_root_intervals(BigRat::from(1));
+        assert_eq!(2, roots.len());
+        assert!(roots[0].bounds().1 < BigRat::zero() && roots[1].bounds().0 > BigRat::zero());
+
+        for root in &roots {
+            let (lo, hi) = root.bounds();
+            assert!(hi.clone() - lo.clone() <= BigRat::from(1));
+            assert!(poly.eval(&lo) * poly.eval(&hi) < BigRat::zero());
+        }
+    }
+
+    #[test]
+    fn squarefree_decomposition() {
+        // (x - 1)(x^2 - 2)^2 x^3
+        let poly = UPoly(vec![1, -1, -4, 4, 4, -4, 0, 0, 0].into_iter().map(Rat::from).collect::<Vec<_>>());
+        let factors = poly.squarefree_decomposition();
+
+        assert_eq!(
+            vec![
+                (UPoly(vec![Rat::from(1), Rat::from(-1)]), 1),
+                (UPoly(vec![Rat::from(1), Rat::from(0), Rat::from(-2)]), 2),
+                (UPoly(vec![Rat::from(1), Rat::from(0)]), 3),
+            ],
+            factors
+        );
+
+        let tol = Rat::from(1) / Rat::from(10000);
+        let roots = poly.real_root_multiplicities(tol);
+
+        assert_eq!(vec![2, 3, 1, 2], roots.iter().map(|(_, n)| *n).collect::<Vec<_>>());
+        assert!((f64::from(roots[3].0.approx()) - 2f64.sqrt()).abs() < f64::from(tol));
+
+        // (x^2 - 2)^2 (5x - 7): sqrt(2) and 7/5 are closer than the tolerance
+        let poly = UPoly(vec![Rat::from(1), Rat::from(0), Rat::from(-2)]);
+        let poly = poly.clone() * poly * UPoly(vec![Rat::from(5), Rat::from(-7)]);
+        let roots = poly.real_root_multiplicities(Rat::from(1) / Rat::from(8));
+
+        assert_eq!(vec![2, 1, 2], roots.iter().map(|(_, n)| *n).collect::<Vec<_>>());
+
+        for pair in roots.windows(2) {
+            assert!(pair[0].0.bounds().1 <= pair[1].0.bounds().0);
+        }
+    }
+
+    #[test]
+    fn sturm() {
+        // x^3 - 2x, with roots -sqrt(2), 0 and sqrt(2)
+        let p = upoly(&[1, 0, -2, 0]);
+        assert_eq!(3, p.count_real_roots());
+        assert_eq!(1, p.count_roots_in(&Rat::from(0), &Rat::from(2)));
+        assert_eq!(2, p.count_roots_in(&Rat::from(-1), &Rat::from(2)));
+        assert_eq!(1, p.count_roots_in(&Rat::from(-1), &Rat::from(0)));
+        assert_eq!(0, p.count_roots_in(&Rat::from(2), &Rat::from(-2)));
+
+        // (x - 1)^2 (x + 1) has two distinct roots
+        let p = upoly(&[1, -1, -1, 1]);
+        assert_eq!(2, p.count_real_roots());
+        assert_eq!(1, p.count_roots_in(&Rat::from(0), &Rat::from(1)));
+
+        assert_eq!(0, upoly(&[1, 0, 1]).count_real_roots());
+        assert_eq!(0, upoly(&[5]).count_real_roots());
+
+        let p = upoly(&[1, -3, -21, 43, 60]);
+        let tol = Rat::from(1) / Rat::from(10000);
+        assert_eq!(p.real_root_intervals(tol).len(), p.count_real_roots());
+
+        // 1 / x jumps up at zero, and -x / (x^2 - 1) down at -1 and 1
+        assert_eq!(1, upoly(&[1, 0]).cauchy_index(&upoly(&[1]), &Rat::from(-1), &Rat::from(1)));
+        assert_eq!(
+            -2,
+            upoly(&[1, 0, -1]).cauchy_index(&upoly(&[-1, 0]), &Rat::from(-2), &Rat::from(2))
+        );
+    }
+
+    #[test]
+    fn hermite() {
+        // x^3 - 2x is positive just past its roots, x - 1 only at sqrt(2)
+        let p = upoly(&[1, 0, -2, 0]);
+        assert_eq!(3, p.count_real_roots_where_positive(&upoly(&[1])));
+        assert_eq!(1, p.count_real_roots_where_positive(&upoly(&[1, -1])));
+        assert_eq!(2, p.count_real_roots_where_positive(&upoly(&[-1, 1])));
+        assert_eq!(2, p.count_real_roots_where_positive(&upoly(&[1, 0, 0])));
+
+        // (x - 1)^2 (x^2 + 1)(x + 2): repeated and complex roots count once or not at all
+        let p = upoly(&[1, 0, -2, 2, -3, 2]);
+        assert_eq!(2, p.count_real_roots_where_positive(&upoly(&[1])));
+        assert_eq!(1, p.count_real_roots_where_positive(&upoly(&[1, 0])));
+        assert_eq!(0, p.count_real_roots_where_positive(&upoly(&[-1])));
+
+        // wilkinson's (x - 1)...(x - 12), whose power sums are far past 64 bits
+        let p = (1..=12).fold(upoly(&[1]), |acc, i| acc * upoly(&[1, -i]));
+        assert_eq!(7, p.count_real_roots_where_positive(&upoly(&[1, -5])));
+
+        let p = (1..=10).fold(upoly(&[1]), |acc, i| acc * upoly(&[7, -i]));
+        assert_eq!(5, p.count_real_roots_where_positive(&upoly(&[7, -5])));
+    }
 }